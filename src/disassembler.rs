@@ -0,0 +1,191 @@
+pub mod arm;
+mod arm_test;
+pub mod riscv;
+mod riscv_test;
+pub mod symbol;
+pub mod x86;
+mod x86_test;
+
+use crate::loader::Endian;
+use arm::MappingSymbol;
+use symbol::SymbolMap;
+
+pub trait Disassembler {
+    fn can_decode(&self) -> bool;
+    fn decode(&mut self) -> Instruction;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControl {
+    Next,
+    UnconditionalBranch,
+    ConditionalBranch,
+    IndirectBranch,
+    Call,
+    IndirectCall,
+    Return,
+    Exception,
+}
+
+pub struct Instruction {
+    pub ip: u64,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    /// lowercase mnemonic that does not change with `FormatOptions`, used for statistics
+    pub base_mnemonic: String,
+    pub operands: String,
+    pub flow_control: FlowControl,
+    pub target: Option<u64>,
+}
+
+impl Instruction {
+    pub fn bad(ip: u64, bytes: Vec<u8>) -> Instruction {
+        Instruction {
+            ip,
+            bytes,
+            mnemonic: "(bad)".to_string(),
+            base_mnemonic: "(bad)".to_string(),
+            operands: String::new(),
+            flow_control: FlowControl::Next,
+            target: None,
+        }
+    }
+
+    pub fn is_call(&self) -> bool {
+        matches!(
+            self.flow_control,
+            FlowControl::Call | FlowControl::IndirectCall
+        )
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.operands.is_empty() {
+            write!(f, "{}", self.mnemonic)
+        } else {
+            write!(f, "{:<9} {}", self.mnemonic, self.operands)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Att,
+    Intel,
+    Masm,
+    Nasm,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HexStyle {
+    Prefix,
+    Suffix,
+}
+
+/// Listing options shared by `-d` and `-a`.
+/// `syntax`, `hex_style` and `digit_separator` only affect x86 listings.
+#[derive(Clone, Copy)]
+pub struct FormatOptions {
+    pub syntax: Syntax,
+    pub uppercase: bool,
+    pub hex_style: Option<HexStyle>,
+    pub digit_separator: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            syntax: Syntax::Nasm,
+            uppercase: false,
+            hex_style: None,
+            digit_separator: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Isa {
+    X86(u32),
+    RiscV(u32),
+    /// AArch64 instructions are little endian, even in big-endian files
+    AArch64,
+    /// initial state and byte order of the instructions
+    Arm(arm::ArmState, Endian),
+}
+
+impl Isa {
+    pub fn disassembler<'a>(
+        &self,
+        bytes: &'a [u8],
+        ip: u64,
+        mapping_symbols: &[MappingSymbol],
+        options: &FormatOptions,
+        symbols: &SymbolMap,
+    ) -> Box<dyn Disassembler + 'a> {
+        let decoder: Box<dyn Disassembler + 'a> = match *self {
+            Isa::X86(bitness) => {
+                return Box::new(x86::Decoder::with_options(
+                    bitness,
+                    bytes,
+                    ip,
+                    options,
+                    symbols.clone(),
+                ))
+            }
+            Isa::RiscV(xlen) => Box::new(riscv::Decoder::with_ip(xlen, bytes, ip)),
+            Isa::AArch64 => Box::new(arm::Decoder::aarch64(bytes, ip, mapping_symbols)),
+            Isa::Arm(state, endian) => {
+                Box::new(arm::Decoder::arm(bytes, ip, state, endian, mapping_symbols))
+            }
+        };
+
+        Box::new(Annotate {
+            decoder,
+            uppercase: options.uppercase,
+            symbols: symbols.clone(),
+        })
+    }
+}
+
+/// Applies `FormatOptions` and symbol names to the output of the native decoders.
+struct Annotate<'a> {
+    decoder: Box<dyn Disassembler + 'a>,
+    uppercase: bool,
+    symbols: SymbolMap,
+}
+
+impl Disassembler for Annotate<'_> {
+    fn can_decode(&self) -> bool {
+        self.decoder.can_decode()
+    }
+
+    fn decode(&mut self) -> Instruction {
+        let mut instruction = self.decoder.decode();
+        if self.uppercase {
+            instruction.mnemonic = instruction.mnemonic.to_uppercase();
+        }
+        if let Some(name) = instruction.target.and_then(|t| self.symbols.resolve(t)) {
+            instruction.operands = format!("{} <{name}>", instruction.operands);
+        }
+        instruction
+    }
+}
+
+pub fn print_label(addr: u64, name: &str) {
+    println!("\n{addr:016X} <{name}>:");
+}
+
+pub fn print_line(instruction: &Instruction) {
+    const HEXBYTES_COLUMN_BYTE_LENGTH: usize = 10;
+    print!("{:016X} ", instruction.ip);
+    for b in instruction.bytes.iter() {
+        print!("{b:02X}");
+    }
+    if instruction.bytes.len() < HEXBYTES_COLUMN_BYTE_LENGTH {
+        for _ in 0..HEXBYTES_COLUMN_BYTE_LENGTH - instruction.bytes.len() {
+            print!("  ");
+        }
+    }
+    println!(" {instruction}");
+}
@@ -0,0 +1,261 @@
+use super::{Disassembler, FlowControl, Instruction};
+use crate::loader::Endian;
+use capstone::arch::arm::{ArmCC, ArmOperandType};
+use capstone::arch::arm64::{Arm64CC, Arm64OperandType};
+use capstone::arch::{self, ArchDetail, ArchOperand, BuildsCapstone, BuildsCapstoneEndian};
+use capstone::{Capstone, InsnGroupType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmState {
+    Arm,
+    Thumb,
+    Data,
+}
+
+/// Code/data state switch taken from `$a`, `$t`, `$x` and `$d` symbols.
+#[derive(Debug, Clone, Copy)]
+pub struct MappingSymbol {
+    pub addr: u64,
+    pub state: ArmState,
+}
+
+impl MappingSymbol {
+    pub fn from_name(name: &str, addr: u64) -> Option<MappingSymbol> {
+        let state = match name.split('.').next() {
+            Some("$a") | Some("$x") => ArmState::Arm,
+            Some("$t") => ArmState::Thumb,
+            Some("$d") => ArmState::Data,
+            _ => return None,
+        };
+        Some(MappingSymbol { addr, state })
+    }
+}
+
+pub struct Decoder<'a> {
+    arm: Capstone,
+    thumb: Option<Capstone>,
+    bytes: &'a [u8],
+    ip: u64,
+    position: usize,
+    state: ArmState,
+    endian: Endian,
+    mapping_symbols: Vec<MappingSymbol>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn aarch64(bytes: &'a [u8], ip: u64, mapping_symbols: &[MappingSymbol]) -> Decoder<'a> {
+        let arm = Capstone::new()
+            .arm64()
+            .mode(arch::arm64::ArchMode::Arm)
+            .detail(true)
+            .build()
+            .expect("failed to initialize AArch64 decoder");
+
+        Self::new(
+            arm,
+            None,
+            bytes,
+            ip,
+            ArmState::Arm,
+            Endian::Little,
+            mapping_symbols,
+        )
+    }
+
+    /// `endian` is the byte order of the instructions, big only in BE32 code.
+    pub fn arm(
+        bytes: &'a [u8],
+        ip: u64,
+        state: ArmState,
+        endian: Endian,
+        mapping_symbols: &[MappingSymbol],
+    ) -> Decoder<'a> {
+        let cs_endian = match endian {
+            Endian::Little => capstone::Endian::Little,
+            Endian::Big => capstone::Endian::Big,
+        };
+        let arm = Capstone::new()
+            .arm()
+            .mode(arch::arm::ArchMode::Arm)
+            .endian(cs_endian)
+            .detail(true)
+            .build()
+            .expect("failed to initialize ARM decoder");
+        let thumb = Capstone::new()
+            .arm()
+            .mode(arch::arm::ArchMode::Thumb)
+            .endian(cs_endian)
+            .detail(true)
+            .build()
+            .expect("failed to initialize Thumb decoder");
+
+        Self::new(arm, Some(thumb), bytes, ip, state, endian, mapping_symbols)
+    }
+
+    fn new(
+        arm: Capstone,
+        thumb: Option<Capstone>,
+        bytes: &'a [u8],
+        ip: u64,
+        state: ArmState,
+        endian: Endian,
+        mapping_symbols: &[MappingSymbol],
+    ) -> Decoder<'a> {
+        let mut mapping_symbols = mapping_symbols.to_vec();
+        mapping_symbols.sort_by_key(|m| m.addr);
+
+        Decoder {
+            arm,
+            thumb,
+            bytes,
+            ip,
+            position: 0,
+            state,
+            endian,
+            mapping_symbols,
+        }
+    }
+
+    fn decode_data(&self, ip: u64, rest: &[u8]) -> Instruction {
+        let next_mapping = self
+            .mapping_symbols
+            .iter()
+            .find(|m| m.addr > ip)
+            .map(|m| (m.addr - ip) as usize)
+            .unwrap_or(rest.len());
+        let (mnemonic, len) = match next_mapping.min(rest.len()) {
+            0..=1 => (".byte", 1),
+            2..=3 => (".short", 2),
+            _ => (".word", 4),
+        };
+        let value = match self.endian {
+            Endian::Little => rest[..len]
+                .iter()
+                .rev()
+                .fold(0_u32, |acc, b| acc << 8 | *b as u32),
+            Endian::Big => rest[..len]
+                .iter()
+                .fold(0_u32, |acc, b| acc << 8 | *b as u32),
+        };
+
+        Instruction {
+            ip,
+            bytes: rest[..len].to_vec(),
+            mnemonic: mnemonic.to_string(),
+            base_mnemonic: mnemonic.to_string(),
+            operands: format!("{value:#0width$x}", width = len * 2 + 2),
+            flow_control: FlowControl::Next,
+            target: None,
+        }
+    }
+
+    fn decode_code(cs: &Capstone, ip: u64, rest: &[u8], unit: usize) -> Instruction {
+        let insns = match cs.disasm_count(rest, ip, 1) {
+            Ok(insns) => insns,
+            Err(_) => return Instruction::bad(ip, rest[..unit.min(rest.len())].to_vec()),
+        };
+        let insn = match insns.iter().next() {
+            Some(insn) => insn,
+            None => return Instruction::bad(ip, rest[..unit.min(rest.len())].to_vec()),
+        };
+
+        let mnemonic = insn.mnemonic().unwrap_or("").to_string();
+        let operands = insn.op_str().unwrap_or("").to_string();
+        let (flow_control, target) = match cs.insn_detail(&insn) {
+            Ok(detail) => {
+                let groups = detail.groups().map(|g| g.0 as u32).collect::<Vec<u32>>();
+                let arch_detail = detail.arch_detail();
+                let target = arch_detail.operands().iter().rev().find_map(|op| match op {
+                    ArchOperand::ArmOperand(op) => match op.op_type {
+                        ArmOperandType::Imm(imm) => Some(imm as u32 as u64),
+                        _ => None,
+                    },
+                    ArchOperand::Arm64Operand(op) => match op.op_type {
+                        Arm64OperandType::Imm(imm) => Some(imm as u64),
+                        _ => None,
+                    },
+                    _ => None,
+                });
+                let conditional = match arch_detail {
+                    ArchDetail::ArmDetail(ref d) => {
+                        !matches!(d.cc(), ArmCC::ARM_CC_AL | ArmCC::ARM_CC_INVALID)
+                    }
+                    ArchDetail::Arm64Detail(ref d) => {
+                        !matches!(d.cc(), Arm64CC::ARM64_CC_AL | Arm64CC::ARM64_CC_INVALID)
+                    }
+                    _ => false,
+                } || matches!(mnemonic.as_str(), "cbz" | "cbnz" | "tbz" | "tbnz");
+                classify(&groups, &mnemonic, &operands, conditional, target)
+            }
+            Err(_) => (FlowControl::Next, None),
+        };
+
+        Instruction {
+            ip,
+            bytes: insn.bytes().to_vec(),
+            base_mnemonic: mnemonic.clone(),
+            mnemonic,
+            operands,
+            flow_control,
+            target,
+        }
+    }
+}
+
+impl Disassembler for Decoder<'_> {
+    fn can_decode(&self) -> bool {
+        self.position < self.bytes.len()
+    }
+
+    fn decode(&mut self) -> Instruction {
+        let ip = self.ip + self.position as u64;
+        if let Some(m) = self.mapping_symbols.iter().rev().find(|m| m.addr <= ip) {
+            self.state = m.state;
+        }
+
+        let rest = &self.bytes[self.position..];
+        let instruction = match self.state {
+            ArmState::Data => self.decode_data(ip, rest),
+            ArmState::Thumb => match &self.thumb {
+                Some(thumb) => Self::decode_code(thumb, ip, rest, 2),
+                None => Self::decode_code(&self.arm, ip, rest, 4),
+            },
+            ArmState::Arm => Self::decode_code(&self.arm, ip, rest, 4),
+        };
+        self.position += instruction.bytes.len();
+
+        instruction
+    }
+}
+
+fn classify(
+    groups: &[u32],
+    mnemonic: &str,
+    operands: &str,
+    conditional: bool,
+    target: Option<u64>,
+) -> (FlowControl, Option<u64>) {
+    let writes_pc = operands.split([',', '{', '}', ' ']).any(|op| op == "pc");
+    let is_return = groups.contains(&InsnGroupType::CS_GRP_RET)
+        || (mnemonic.starts_with("bx") && operands == "lr")
+        || ((mnemonic.starts_with("pop") || mnemonic.starts_with("ldm")) && writes_pc);
+
+    if is_return {
+        (FlowControl::Return, None)
+    } else if groups.contains(&InsnGroupType::CS_GRP_CALL) {
+        match target {
+            Some(target) => (FlowControl::Call, Some(target)),
+            None => (FlowControl::IndirectCall, None),
+        }
+    } else if groups.contains(&InsnGroupType::CS_GRP_JUMP) {
+        match (target, conditional) {
+            (Some(target), true) => (FlowControl::ConditionalBranch, Some(target)),
+            (Some(target), false) => (FlowControl::UnconditionalBranch, Some(target)),
+            (None, _) => (FlowControl::IndirectBranch, None),
+        }
+    } else if groups.contains(&InsnGroupType::CS_GRP_INT) {
+        (FlowControl::Exception, None)
+    } else {
+        (FlowControl::Next, None)
+    }
+}
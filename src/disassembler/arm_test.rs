@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use crate::disassembler::arm::{ArmState, Decoder, MappingSymbol};
+    use crate::disassembler::{Disassembler, FlowControl};
+    use crate::loader::Endian;
+
+    #[test]
+    fn aarch64_test() {
+        let bytes = [
+            0xfd, 0x7b, 0xbf, 0xa9, // stp x29, x30, [sp, #-0x10]!
+            0x05, 0x00, 0x00, 0x94, // bl #0x1018
+            0x40, 0x00, 0x00, 0x34, // cbz w0, #0x1010
+            0xc0, 0x03, 0x5f, 0xd6, // ret
+        ];
+        let mut decoder = Decoder::aarch64(&bytes, 0x1000, &[]);
+        let mut output = Vec::new();
+        while decoder.can_decode() {
+            output.push(decoder.decode());
+        }
+
+        assert_eq!(output[0].to_string(), "stp       x29, x30, [sp, #-0x10]!");
+        assert_eq!(output[1].flow_control, FlowControl::Call);
+        assert_eq!(output[1].target, Some(0x1018));
+        assert_eq!(output[2].flow_control, FlowControl::ConditionalBranch);
+        assert_eq!(output[2].target, Some(0x1010));
+        assert_eq!(output[3].flow_control, FlowControl::Return);
+    }
+
+    #[test]
+    fn arm_thumb_data_test() {
+        let bytes = [
+            0x00, 0x48, 0x2d, 0xe9, // push {fp, lr}
+            0x00, 0x88, 0xbd, 0xe8, // pop {fp, pc}
+            0x78, 0x56, 0x34, 0x12, // .word 0x12345678
+            0x40, 0x43, // muls r0, r0, r0
+            0x70, 0x47, // bx lr
+        ];
+        let mapping_symbols = [
+            MappingSymbol::from_name("$a.0", 0).unwrap(),
+            MappingSymbol::from_name("$d.1", 8).unwrap(),
+            MappingSymbol::from_name("$t.2", 12).unwrap(),
+        ];
+        let mut decoder = Decoder::arm(&bytes, 0, ArmState::Arm, Endian::Little, &mapping_symbols);
+        let mut output = Vec::new();
+        while decoder.can_decode() {
+            output.push(decoder.decode());
+        }
+
+        let text = output.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            text,
+            vec![
+                "push      {fp, lr}",
+                "pop       {fp, pc}",
+                ".word     0x12345678",
+                "muls      r0, r0, r0",
+                "bx        lr",
+            ]
+        );
+        assert_eq!(output[1].flow_control, FlowControl::Return);
+        assert_eq!(output[4].flow_control, FlowControl::Return);
+    }
+
+    #[test]
+    fn arm_big_endian_test() {
+        // BE32 code, as in big-endian relocatable objects
+        let bytes = [
+            0xe9, 0x2d, 0x48, 0x00, // push {fp, lr}
+            0x12, 0x34, 0x56, 0x78, // .word 0x12345678
+            0x43, 0x40, // muls r0, r0, r0
+            0x47, 0x70, // bx lr
+        ];
+        let mapping_symbols = [
+            MappingSymbol::from_name("$a.0", 0).unwrap(),
+            MappingSymbol::from_name("$d.1", 4).unwrap(),
+            MappingSymbol::from_name("$t.2", 8).unwrap(),
+        ];
+        let mut decoder = Decoder::arm(&bytes, 0, ArmState::Arm, Endian::Big, &mapping_symbols);
+        let mut output = Vec::new();
+        while decoder.can_decode() {
+            output.push(decoder.decode());
+        }
+
+        let text = output.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            text,
+            vec![
+                "push      {fp, lr}",
+                ".word     0x12345678",
+                "muls      r0, r0, r0",
+                "bx        lr",
+            ]
+        );
+    }
+}
@@ -0,0 +1,951 @@
+use super::{Disassembler, FlowControl, Instruction};
+
+const REG_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const FREG_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+const RM_NAMES: [&str; 8] = ["rne", "rtz", "rdn", "rup", "rmm", "", "", "dyn"];
+
+const RA: u32 = 1;
+const T0: u32 = 5;
+
+fn reg(r: u32) -> &'static str {
+    REG_NAMES[r as usize & 0x1f]
+}
+
+fn freg(r: u32) -> &'static str {
+    FREG_NAMES[r as usize & 0x1f]
+}
+
+fn csr_name(csr: u32) -> String {
+    let name = match csr {
+        0x001 => "fflags",
+        0x002 => "frm",
+        0x003 => "fcsr",
+        0x100 => "sstatus",
+        0x104 => "sie",
+        0x105 => "stvec",
+        0x106 => "scounteren",
+        0x10a => "senvcfg",
+        0x140 => "sscratch",
+        0x141 => "sepc",
+        0x142 => "scause",
+        0x143 => "stval",
+        0x144 => "sip",
+        0x180 => "satp",
+        0x300 => "mstatus",
+        0x301 => "misa",
+        0x302 => "medeleg",
+        0x303 => "mideleg",
+        0x304 => "mie",
+        0x305 => "mtvec",
+        0x306 => "mcounteren",
+        0x30a => "menvcfg",
+        0x310 => "mstatush",
+        0x320 => "mcountinhibit",
+        0x340 => "mscratch",
+        0x341 => "mepc",
+        0x342 => "mcause",
+        0x343 => "mtval",
+        0x344 => "mip",
+        0x34a => "mtinst",
+        0x34b => "mtval2",
+        0x7a0 => "tselect",
+        0x7a1 => "tdata1",
+        0x7a2 => "tdata2",
+        0x7a3 => "tdata3",
+        0x7b0 => "dcsr",
+        0x7b1 => "dpc",
+        0x7b2 => "dscratch0",
+        0x7b3 => "dscratch1",
+        0xb00 => "mcycle",
+        0xb02 => "minstret",
+        0xb80 => "mcycleh",
+        0xb82 => "minstreth",
+        0xc00 => "cycle",
+        0xc01 => "time",
+        0xc02 => "instret",
+        0xc80 => "cycleh",
+        0xc81 => "timeh",
+        0xc82 => "instreth",
+        0xf11 => "mvendorid",
+        0xf12 => "marchid",
+        0xf13 => "mimpid",
+        0xf14 => "mhartid",
+        0xf15 => "mconfigptr",
+        0x3a0..=0x3af => return format!("pmpcfg{}", csr - 0x3a0),
+        0x3b0..=0x3ef => return format!("pmpaddr{}", csr - 0x3b0),
+        0x323..=0x33f => return format!("mhpmevent{}", csr - 0x320),
+        0xb03..=0xb1f => return format!("mhpmcounter{}", csr - 0xb00),
+        0xb83..=0xb9f => return format!("mhpmcounter{}h", csr - 0xb80),
+        0xc03..=0xc1f => return format!("hpmcounter{}", csr - 0xc00),
+        0xc83..=0xc9f => return format!("hpmcounter{}h", csr - 0xc80),
+        _ => return format!("{csr:#x}"),
+    };
+    name.to_string()
+}
+
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as i64
+}
+
+fn bits(inst: u32, hi: u32, lo: u32) -> u32 {
+    (inst >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+pub struct Decoder<'a> {
+    xlen: u32,
+    bytes: &'a [u8],
+    ip: u64,
+    position: usize,
+    // (rd, value) of the preceding auipc, used to resolve `auipc + jalr` call pairs
+    last_auipc: Option<(u32, u64)>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn with_ip(xlen: u32, bytes: &'a [u8], ip: u64) -> Decoder<'a> {
+        Decoder {
+            xlen,
+            bytes,
+            ip,
+            position: 0,
+            last_auipc: None,
+        }
+    }
+
+    fn decode_32(&mut self, inst: u32, ip: u64, bytes: &[u8]) -> Option<Instruction> {
+        let opcode = bits(inst, 6, 0);
+        let rd = bits(inst, 11, 7);
+        let funct3 = bits(inst, 14, 12);
+        let rs1 = bits(inst, 19, 15);
+        let rs2 = bits(inst, 24, 20);
+        let funct7 = bits(inst, 31, 25);
+        let imm_i = sign_extend(bits(inst, 31, 20), 12);
+        let imm_s = sign_extend(bits(inst, 31, 25) << 5 | bits(inst, 11, 7), 12);
+        let imm_b = sign_extend(
+            bits(inst, 31, 31) << 12
+                | bits(inst, 7, 7) << 11
+                | bits(inst, 30, 25) << 5
+                | bits(inst, 11, 8) << 1,
+            13,
+        );
+        let imm_j = sign_extend(
+            bits(inst, 31, 31) << 20
+                | bits(inst, 19, 12) << 12
+                | bits(inst, 20, 20) << 11
+                | bits(inst, 30, 21) << 1,
+            21,
+        );
+        let is_64 = self.xlen == 64;
+        let mut flow_control = FlowControl::Next;
+        let mut target = None;
+        let last_auipc = self.last_auipc.take();
+
+        let (mnemonic, operands): (String, String) = match opcode {
+            // LUI
+            0x37 => ("lui".into(), format!("{},{:#x}", reg(rd), inst >> 12)),
+            // AUIPC
+            0x17 => {
+                let value = ip.wrapping_add(sign_extend(inst & 0xffff_f000, 32) as u64);
+                self.last_auipc = Some((rd, value));
+                ("auipc".into(), format!("{},{:#x}", reg(rd), inst >> 12))
+            }
+            // JAL
+            0x6f => {
+                let dest = ip.wrapping_add(imm_j as u64);
+                target = Some(dest);
+                match rd {
+                    0 => {
+                        flow_control = FlowControl::UnconditionalBranch;
+                        ("j".into(), format!("{dest:#x}"))
+                    }
+                    RA => {
+                        flow_control = FlowControl::Call;
+                        ("jal".into(), format!("{dest:#x}"))
+                    }
+                    _ => {
+                        if rd == T0 {
+                            flow_control = FlowControl::Call;
+                        } else {
+                            flow_control = FlowControl::UnconditionalBranch;
+                        }
+                        ("jal".into(), format!("{},{dest:#x}", reg(rd)))
+                    }
+                }
+            }
+            // JALR
+            0x67 if funct3 == 0 => {
+                if let Some((auipc_rd, value)) = last_auipc {
+                    if auipc_rd == rs1 {
+                        target = Some(value.wrapping_add(imm_i as u64));
+                    }
+                }
+                flow_control = match (rd, rs1) {
+                    (0, RA) => FlowControl::Return,
+                    (0, _) => FlowControl::IndirectBranch,
+                    _ if target.is_some() => FlowControl::Call,
+                    _ => FlowControl::IndirectCall,
+                };
+                match (rd, rs1, imm_i) {
+                    (0, RA, 0) => ("ret".into(), String::new()),
+                    (0, _, 0) => ("jr".into(), reg(rs1).into()),
+                    (RA, _, 0) => ("jalr".into(), reg(rs1).into()),
+                    _ => (
+                        "jalr".into(),
+                        format!("{},{}({})", reg(rd), imm_i, reg(rs1)),
+                    ),
+                }
+            }
+            // BRANCH
+            0x63 => {
+                let dest = ip.wrapping_add(imm_b as u64);
+                target = Some(dest);
+                flow_control = FlowControl::ConditionalBranch;
+                let name = match funct3 {
+                    0 => "beq",
+                    1 => "bne",
+                    4 => "blt",
+                    5 => "bge",
+                    6 => "bltu",
+                    7 => "bgeu",
+                    _ => return None,
+                };
+                match (name, rs1, rs2) {
+                    ("beq", _, 0) | ("bne", _, 0) | ("blt", _, 0) | ("bge", _, 0) => {
+                        (format!("{name}z"), format!("{},{dest:#x}", reg(rs1)))
+                    }
+                    ("blt", 0, _) => ("bgtz".into(), format!("{},{dest:#x}", reg(rs2))),
+                    ("bge", 0, _) => ("blez".into(), format!("{},{dest:#x}", reg(rs2))),
+                    _ => (name.into(), format!("{},{},{dest:#x}", reg(rs1), reg(rs2))),
+                }
+            }
+            // LOAD
+            0x03 => {
+                let name = match funct3 {
+                    0 => "lb",
+                    1 => "lh",
+                    2 => "lw",
+                    3 if is_64 => "ld",
+                    4 => "lbu",
+                    5 => "lhu",
+                    6 if is_64 => "lwu",
+                    _ => return None,
+                };
+                (name.into(), format!("{},{}({})", reg(rd), imm_i, reg(rs1)))
+            }
+            // STORE
+            0x23 => {
+                let name = match funct3 {
+                    0 => "sb",
+                    1 => "sh",
+                    2 => "sw",
+                    3 if is_64 => "sd",
+                    _ => return None,
+                };
+                (name.into(), format!("{},{}({})", reg(rs2), imm_s, reg(rs1)))
+            }
+            // OP-IMM
+            0x13 => {
+                let (shamt, shift_funct) = if is_64 {
+                    (bits(inst, 25, 20), bits(inst, 31, 26) << 1)
+                } else {
+                    (bits(inst, 24, 20), bits(inst, 31, 25))
+                };
+                match funct3 {
+                    0 => match (rd, rs1, imm_i) {
+                        (0, 0, 0) => ("nop".into(), String::new()),
+                        (_, 0, _) => ("li".into(), format!("{},{}", reg(rd), imm_i)),
+                        (_, _, 0) => ("mv".into(), format!("{},{}", reg(rd), reg(rs1))),
+                        _ => ("addi".into(), format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                    },
+                    1 if shift_funct == 0 => (
+                        "slli".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    2 => ("slti".into(), format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                    3 if imm_i == 1 => ("seqz".into(), format!("{},{}", reg(rd), reg(rs1))),
+                    3 => (
+                        "sltiu".into(),
+                        format!("{},{},{}", reg(rd), reg(rs1), imm_i),
+                    ),
+                    4 if imm_i == -1 => ("not".into(), format!("{},{}", reg(rd), reg(rs1))),
+                    4 => ("xori".into(), format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                    5 if shift_funct == 0 => (
+                        "srli".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    5 if shift_funct == 0x20 => (
+                        "srai".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    6 => ("ori".into(), format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                    7 => ("andi".into(), format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                    _ => return None,
+                }
+            }
+            // OP-IMM-32
+            0x1b if is_64 => {
+                let shamt = bits(inst, 24, 20);
+                match (funct3, funct7) {
+                    (0, _) if imm_i == 0 => ("sext.w".into(), format!("{},{}", reg(rd), reg(rs1))),
+                    (0, _) => (
+                        "addiw".into(),
+                        format!("{},{},{}", reg(rd), reg(rs1), imm_i),
+                    ),
+                    (1, 0x00) => (
+                        "slliw".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    (5, 0x00) => (
+                        "srliw".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    (5, 0x20) => (
+                        "sraiw".into(),
+                        format!("{},{},{:#x}", reg(rd), reg(rs1), shamt),
+                    ),
+                    _ => return None,
+                }
+            }
+            // OP
+            0x33 => {
+                let name = match (funct7, funct3) {
+                    (0x00, 0) => "add",
+                    (0x20, 0) => "sub",
+                    (0x00, 1) => "sll",
+                    (0x00, 2) => "slt",
+                    (0x00, 3) => "sltu",
+                    (0x00, 4) => "xor",
+                    (0x00, 5) => "srl",
+                    (0x20, 5) => "sra",
+                    (0x00, 6) => "or",
+                    (0x00, 7) => "and",
+                    (0x01, 0) => "mul",
+                    (0x01, 1) => "mulh",
+                    (0x01, 2) => "mulhsu",
+                    (0x01, 3) => "mulhu",
+                    (0x01, 4) => "div",
+                    (0x01, 5) => "divu",
+                    (0x01, 6) => "rem",
+                    (0x01, 7) => "remu",
+                    _ => return None,
+                };
+                match (name, rs1) {
+                    ("add", 0) => ("mv".into(), format!("{},{}", reg(rd), reg(rs2))),
+                    ("sub", 0) => ("neg".into(), format!("{},{}", reg(rd), reg(rs2))),
+                    ("sltu", 0) => ("snez".into(), format!("{},{}", reg(rd), reg(rs2))),
+                    _ => (
+                        name.into(),
+                        format!("{},{},{}", reg(rd), reg(rs1), reg(rs2)),
+                    ),
+                }
+            }
+            // OP-32
+            0x3b if is_64 => {
+                let name = match (funct7, funct3) {
+                    (0x00, 0) => "addw",
+                    (0x20, 0) => "subw",
+                    (0x00, 1) => "sllw",
+                    (0x00, 5) => "srlw",
+                    (0x20, 5) => "sraw",
+                    (0x01, 0) => "mulw",
+                    (0x01, 4) => "divw",
+                    (0x01, 5) => "divuw",
+                    (0x01, 6) => "remw",
+                    (0x01, 7) => "remuw",
+                    _ => return None,
+                };
+                match (name, rs1) {
+                    ("subw", 0) => ("negw".into(), format!("{},{}", reg(rd), reg(rs2))),
+                    _ => (
+                        name.into(),
+                        format!("{},{},{}", reg(rd), reg(rs1), reg(rs2)),
+                    ),
+                }
+            }
+            // MISC-MEM
+            0x0f => match funct3 {
+                0 => {
+                    let fence_set = |set: u32| {
+                        ["i", "o", "r", "w"]
+                            .iter()
+                            .enumerate()
+                            .filter(|(i, _)| set >> (3 - i) & 1 == 1)
+                            .map(|(_, c)| *c)
+                            .collect::<String>()
+                    };
+                    let pred = bits(inst, 27, 24);
+                    let succ = bits(inst, 23, 20);
+                    match (bits(inst, 31, 28), pred, succ) {
+                        (0b1000, 0b0011, 0b0011) => ("fence.tso".into(), String::new()),
+                        (_, 0b1111, 0b1111) => ("fence".into(), String::new()),
+                        _ => (
+                            "fence".into(),
+                            format!("{},{}", fence_set(pred), fence_set(succ)),
+                        ),
+                    }
+                }
+                1 => ("fence.i".into(), String::new()),
+                _ => return None,
+            },
+            // SYSTEM
+            0x73 => {
+                let csr = bits(inst, 31, 20);
+                match funct3 {
+                    0 => {
+                        flow_control = FlowControl::Exception;
+                        match inst {
+                            0x0000_0073 => ("ecall".into(), String::new()),
+                            0x0010_0073 => ("ebreak".into(), String::new()),
+                            0x1020_0073 => ("sret".into(), String::new()),
+                            0x3020_0073 => ("mret".into(), String::new()),
+                            0x7b20_0073 => ("dret".into(), String::new()),
+                            0x1050_0073 => {
+                                flow_control = FlowControl::Next;
+                                ("wfi".into(), String::new())
+                            }
+                            _ if funct7 == 0x09 && rd == 0 => {
+                                flow_control = FlowControl::Next;
+                                ("sfence.vma".into(), format!("{},{}", reg(rs1), reg(rs2)))
+                            }
+                            _ => return None,
+                        }
+                    }
+                    1..=3 => {
+                        let name = ["csrrw", "csrrs", "csrrc"][funct3 as usize - 1];
+                        match (name, rd, rs1) {
+                            ("csrrs", _, 0) => {
+                                ("csrr".into(), format!("{},{}", reg(rd), csr_name(csr)))
+                            }
+                            (_, 0, _) => (
+                                format!("csr{}", &name[4..]),
+                                format!("{},{}", csr_name(csr), reg(rs1)),
+                            ),
+                            _ => (
+                                name.into(),
+                                format!("{},{},{}", reg(rd), csr_name(csr), reg(rs1)),
+                            ),
+                        }
+                    }
+                    5..=7 => {
+                        let name = ["csrrwi", "csrrsi", "csrrci"][funct3 as usize - 5];
+                        match rd {
+                            0 => (
+                                format!("csr{}", &name[4..]),
+                                format!("{},{}", csr_name(csr), rs1),
+                            ),
+                            _ => (
+                                name.into(),
+                                format!("{},{},{}", reg(rd), csr_name(csr), rs1),
+                            ),
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            // AMO
+            0x2f => {
+                let width = match funct3 {
+                    2 => "w",
+                    3 if is_64 => "d",
+                    _ => return None,
+                };
+                let ordering = match bits(inst, 26, 25) {
+                    0b00 => "",
+                    0b01 => ".rl",
+                    0b10 => ".aq",
+                    _ => ".aqrl",
+                };
+                let name = match bits(inst, 31, 27) {
+                    0b00010 if rs2 == 0 => "lr",
+                    0b00011 => "sc",
+                    0b00001 => "amoswap",
+                    0b00000 => "amoadd",
+                    0b00100 => "amoxor",
+                    0b01100 => "amoand",
+                    0b01000 => "amoor",
+                    0b10000 => "amomin",
+                    0b10100 => "amomax",
+                    0b11000 => "amominu",
+                    0b11100 => "amomaxu",
+                    _ => return None,
+                };
+                let operands = if name == "lr" {
+                    format!("{},({})", reg(rd), reg(rs1))
+                } else {
+                    format!("{},{},({})", reg(rd), reg(rs2), reg(rs1))
+                };
+                (format!("{name}.{width}{ordering}"), operands)
+            }
+            // LOAD-FP
+            0x07 => {
+                let name = match funct3 {
+                    2 => "flw",
+                    3 => "fld",
+                    _ => return None,
+                };
+                (name.into(), format!("{},{}({})", freg(rd), imm_i, reg(rs1)))
+            }
+            // STORE-FP
+            0x27 => {
+                let name = match funct3 {
+                    2 => "fsw",
+                    3 => "fsd",
+                    _ => return None,
+                };
+                (
+                    name.into(),
+                    format!("{},{}({})", freg(rs2), imm_s, reg(rs1)),
+                )
+            }
+            // FMADD, FMSUB, FNMSUB, FNMADD
+            0x43 | 0x47 | 0x4b | 0x4f => {
+                let fmt = match bits(inst, 26, 25) {
+                    0 => "s",
+                    1 => "d",
+                    _ => return None,
+                };
+                let name = match opcode {
+                    0x43 => "fmadd",
+                    0x47 => "fmsub",
+                    0x4b => "fnmsub",
+                    _ => "fnmadd",
+                };
+                let rs3 = bits(inst, 31, 27);
+                (
+                    format!("{name}.{fmt}"),
+                    format!(
+                        "{},{},{},{}{}",
+                        freg(rd),
+                        freg(rs1),
+                        freg(rs2),
+                        freg(rs3),
+                        rounding_mode(funct3)?
+                    ),
+                )
+            }
+            // OP-FP
+            0x53 => decode_op_fp(inst, is_64)?,
+            _ => return None,
+        };
+
+        Some(Instruction {
+            ip,
+            bytes: bytes.to_vec(),
+            base_mnemonic: mnemonic.clone(),
+            mnemonic,
+            operands,
+            flow_control,
+            target,
+        })
+    }
+}
+
+impl Disassembler for Decoder<'_> {
+    fn can_decode(&self) -> bool {
+        self.position < self.bytes.len()
+    }
+
+    fn decode(&mut self) -> Instruction {
+        let ip = self.ip + self.position as u64;
+        let rest = &self.bytes[self.position..];
+        let (inst, len) = if rest.len() < 2 {
+            (None, rest.len())
+        } else {
+            let low = rest[0] as u32 | (rest[1] as u32) << 8;
+            if low & 0b11 != 0b11 {
+                (expand_compressed(low, self.xlen), 2)
+            } else if low & 0b11100 == 0b11100 || rest.len() < 4 {
+                // 48-bit and longer encodings are not supported
+                (None, 2)
+            } else {
+                let high = rest[2] as u32 | (rest[3] as u32) << 8;
+                (Some(low | high << 16), 4)
+            }
+        };
+        let inst_bytes = rest[..len].to_vec();
+        self.position += len;
+
+        let decoded = inst.and_then(|inst| self.decode_32(inst, ip, &inst_bytes));
+        match decoded {
+            Some(decoded) => decoded,
+            None => {
+                self.last_auipc = None;
+                Instruction::bad(ip, inst_bytes)
+            }
+        }
+    }
+}
+
+fn rounding_mode(rm: u32) -> Option<String> {
+    match rm {
+        7 => Some(String::new()),
+        5 | 6 => None,
+        _ => Some(format!(",{}", RM_NAMES[rm as usize])),
+    }
+}
+
+fn decode_op_fp(inst: u32, is_64: bool) -> Option<(String, String)> {
+    let rd = bits(inst, 11, 7);
+    let rm = bits(inst, 14, 12);
+    let rs1 = bits(inst, 19, 15);
+    let rs2 = bits(inst, 24, 20);
+    let funct7 = bits(inst, 31, 25);
+    let fmt = match funct7 & 0b11 {
+        0 => "s",
+        1 => "d",
+        _ => return None,
+    };
+    let int_fmt = |sel: u32| match sel {
+        0 => Some("w"),
+        1 => Some("wu"),
+        2 if is_64 => Some("l"),
+        3 if is_64 => Some("lu"),
+        _ => None,
+    };
+
+    let result = match funct7 >> 2 {
+        0b00000..=0b00011 => {
+            let name = ["fadd", "fsub", "fmul", "fdiv"][(funct7 >> 2) as usize];
+            (
+                format!("{name}.{fmt}"),
+                format!(
+                    "{},{},{}{}",
+                    freg(rd),
+                    freg(rs1),
+                    freg(rs2),
+                    rounding_mode(rm)?
+                ),
+            )
+        }
+        0b01011 if rs2 == 0 => (
+            format!("fsqrt.{fmt}"),
+            format!("{},{}{}", freg(rd), freg(rs1), rounding_mode(rm)?),
+        ),
+        0b00100 => {
+            let (name, alias) = match rm {
+                0 => ("fsgnj", "fmv"),
+                1 => ("fsgnjn", "fneg"),
+                2 => ("fsgnjx", "fabs"),
+                _ => return None,
+            };
+            if rs1 == rs2 {
+                (
+                    format!("{alias}.{fmt}"),
+                    format!("{},{}", freg(rd), freg(rs1)),
+                )
+            } else {
+                (
+                    format!("{name}.{fmt}"),
+                    format!("{},{},{}", freg(rd), freg(rs1), freg(rs2)),
+                )
+            }
+        }
+        0b00101 => {
+            let name = match rm {
+                0 => "fmin",
+                1 => "fmax",
+                _ => return None,
+            };
+            (
+                format!("{name}.{fmt}"),
+                format!("{},{},{}", freg(rd), freg(rs1), freg(rs2)),
+            )
+        }
+        0b01000 => match (funct7, rs2) {
+            (0x20, 1) => (
+                "fcvt.s.d".into(),
+                format!("{},{}{}", freg(rd), freg(rs1), rounding_mode(rm)?),
+            ),
+            // widening conversion is exact, the rounding mode is ignored
+            (0x21, 0) => ("fcvt.d.s".into(), format!("{},{}", freg(rd), freg(rs1))),
+            _ => return None,
+        },
+        0b10100 => {
+            let name = match rm {
+                0 => "fle",
+                1 => "flt",
+                2 => "feq",
+                _ => return None,
+            };
+            (
+                format!("{name}.{fmt}"),
+                format!("{},{},{}", reg(rd), freg(rs1), freg(rs2)),
+            )
+        }
+        0b11000 => (
+            format!("fcvt.{}.{fmt}", int_fmt(rs2)?),
+            format!("{},{}{}", reg(rd), freg(rs1), rounding_mode(rm)?),
+        ),
+        0b11010 => {
+            let int_fmt = int_fmt(rs2)?;
+            // 32-bit integers always fit in a double, the rounding mode is ignored
+            let rm = if fmt == "d" && rs2 < 2 {
+                String::new()
+            } else {
+                rounding_mode(rm)?
+            };
+            (
+                format!("fcvt.{fmt}.{int_fmt}"),
+                format!("{},{}{}", freg(rd), reg(rs1), rm),
+            )
+        }
+        0b11100 if rs2 == 0 => match (rm, fmt) {
+            (0, "s") => ("fmv.x.w".into(), format!("{},{}", reg(rd), freg(rs1))),
+            (0, _) if is_64 => ("fmv.x.d".into(), format!("{},{}", reg(rd), freg(rs1))),
+            (1, _) => (
+                format!("fclass.{fmt}"),
+                format!("{},{}", reg(rd), freg(rs1)),
+            ),
+            _ => return None,
+        },
+        0b11110 if rs2 == 0 && rm == 0 => match fmt {
+            "s" => ("fmv.w.x".into(), format!("{},{}", freg(rd), reg(rs1))),
+            _ if is_64 => ("fmv.d.x".into(), format!("{},{}", freg(rd), reg(rs1))),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+fn encode_r(opcode: u32, rd: u32, funct3: u32, rs1: u32, rs2: u32, funct7: u32) -> u32 {
+    funct7 << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn encode_i(opcode: u32, rd: u32, funct3: u32, rs1: u32, imm: i64) -> u32 {
+    ((imm as u32) & 0xfff) << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn encode_s(opcode: u32, funct3: u32, rs1: u32, rs2: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits(imm, 11, 5) << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | bits(imm, 4, 0) << 7 | opcode
+}
+
+fn encode_b(rs1: u32, rs2: u32, funct3: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits(imm, 12, 12) << 31
+        | bits(imm, 10, 5) << 25
+        | rs2 << 20
+        | rs1 << 15
+        | funct3 << 12
+        | bits(imm, 4, 1) << 8
+        | bits(imm, 11, 11) << 7
+        | 0x63
+}
+
+fn encode_j(rd: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits(imm, 20, 20) << 31
+        | bits(imm, 10, 1) << 21
+        | bits(imm, 11, 11) << 20
+        | bits(imm, 19, 12) << 12
+        | rd << 7
+        | 0x6f
+}
+
+/// Expand a 16-bit RVC instruction into its 32-bit equivalent.
+fn expand_compressed(inst: u32, xlen: u32) -> Option<u32> {
+    let is_64 = xlen == 64;
+    let funct3 = bits(inst, 15, 13);
+    // rd/rs1 and rs2 in the full register space
+    let rd = bits(inst, 11, 7);
+    let rs2 = bits(inst, 6, 2);
+    // rd'/rs1' and rs2' in the x8-x15 register window
+    let rd_p = bits(inst, 4, 2) + 8;
+    let rs1_p = bits(inst, 9, 7) + 8;
+    let imm6 = sign_extend(bits(inst, 12, 12) << 5 | bits(inst, 6, 2), 6);
+    const SP: u32 = 2;
+
+    let expanded = match (bits(inst, 1, 0), funct3) {
+        (0b00, 0b000) => {
+            let nzuimm = bits(inst, 10, 7) << 6
+                | bits(inst, 12, 11) << 4
+                | bits(inst, 5, 5) << 3
+                | bits(inst, 6, 6) << 2;
+            if nzuimm == 0 {
+                return None;
+            }
+            encode_i(0x13, rd_p, 0, SP, nzuimm as i64)
+        }
+        (0b00, 0b001) => {
+            let uimm = bits(inst, 6, 5) << 6 | bits(inst, 12, 10) << 3;
+            encode_i(0x07, rd_p, 3, rs1_p, uimm as i64)
+        }
+        (0b00, 0b010) => {
+            let uimm = bits(inst, 5, 5) << 6 | bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2;
+            encode_i(0x03, rd_p, 2, rs1_p, uimm as i64)
+        }
+        (0b00, 0b011) => {
+            if is_64 {
+                let uimm = bits(inst, 6, 5) << 6 | bits(inst, 12, 10) << 3;
+                encode_i(0x03, rd_p, 3, rs1_p, uimm as i64)
+            } else {
+                let uimm = bits(inst, 5, 5) << 6 | bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2;
+                encode_i(0x07, rd_p, 2, rs1_p, uimm as i64)
+            }
+        }
+        (0b00, 0b101) => {
+            let uimm = bits(inst, 6, 5) << 6 | bits(inst, 12, 10) << 3;
+            encode_s(0x27, 3, rs1_p, rd_p, uimm as i64)
+        }
+        (0b00, 0b110) => {
+            let uimm = bits(inst, 5, 5) << 6 | bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2;
+            encode_s(0x23, 2, rs1_p, rd_p, uimm as i64)
+        }
+        (0b00, 0b111) => {
+            if is_64 {
+                let uimm = bits(inst, 6, 5) << 6 | bits(inst, 12, 10) << 3;
+                encode_s(0x23, 3, rs1_p, rd_p, uimm as i64)
+            } else {
+                let uimm = bits(inst, 5, 5) << 6 | bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2;
+                encode_s(0x27, 2, rs1_p, rd_p, uimm as i64)
+            }
+        }
+        (0b01, 0b000) => encode_i(0x13, rd, 0, rd, imm6),
+        (0b01, 0b001) => {
+            if is_64 {
+                if rd == 0 {
+                    return None;
+                }
+                encode_i(0x1b, rd, 0, rd, imm6)
+            } else {
+                encode_j(RA, compressed_jump_offset(inst))
+            }
+        }
+        (0b01, 0b010) => encode_i(0x13, rd, 0, 0, imm6),
+        (0b01, 0b011) => {
+            if rd == SP {
+                let nzimm = sign_extend(
+                    bits(inst, 12, 12) << 9
+                        | bits(inst, 4, 3) << 7
+                        | bits(inst, 5, 5) << 6
+                        | bits(inst, 2, 2) << 5
+                        | bits(inst, 6, 6) << 4,
+                    10,
+                );
+                if nzimm == 0 {
+                    return None;
+                }
+                encode_i(0x13, SP, 0, SP, nzimm)
+            } else {
+                if imm6 == 0 {
+                    return None;
+                }
+                ((imm6 as u32) & 0xfffff) << 12 | rd << 7 | 0x37
+            }
+        }
+        (0b01, 0b100) => {
+            let rd = rs1_p;
+            let shamt = bits(inst, 12, 12) << 5 | bits(inst, 6, 2);
+            match bits(inst, 11, 10) {
+                0b00 => encode_i(0x13, rd, 5, rd, shamt as i64),
+                0b01 => encode_i(0x13, rd, 5, rd, (0x400 | shamt) as i64),
+                0b10 => encode_i(0x13, rd, 7, rd, imm6),
+                _ => {
+                    let rs2 = rd_p;
+                    match (bits(inst, 12, 12), bits(inst, 6, 5)) {
+                        (0, 0b00) => encode_r(0x33, rd, 0, rd, rs2, 0x20),
+                        (0, 0b01) => encode_r(0x33, rd, 4, rd, rs2, 0x00),
+                        (0, 0b10) => encode_r(0x33, rd, 6, rd, rs2, 0x00),
+                        (0, 0b11) => encode_r(0x33, rd, 7, rd, rs2, 0x00),
+                        (1, 0b00) if is_64 => encode_r(0x3b, rd, 0, rd, rs2, 0x20),
+                        (1, 0b01) if is_64 => encode_r(0x3b, rd, 0, rd, rs2, 0x00),
+                        _ => return None,
+                    }
+                }
+            }
+        }
+        (0b01, 0b101) => encode_j(0, compressed_jump_offset(inst)),
+        (0b01, 0b110) | (0b01, 0b111) => {
+            let offset = sign_extend(
+                bits(inst, 12, 12) << 8
+                    | bits(inst, 6, 5) << 6
+                    | bits(inst, 2, 2) << 5
+                    | bits(inst, 11, 10) << 3
+                    | bits(inst, 4, 3) << 1,
+                9,
+            );
+            encode_b(rs1_p, 0, funct3 & 1, offset)
+        }
+        (0b10, 0b000) => {
+            let shamt = bits(inst, 12, 12) << 5 | bits(inst, 6, 2);
+            encode_i(0x13, rd, 1, rd, shamt as i64)
+        }
+        (0b10, 0b001) => {
+            let uimm = bits(inst, 4, 2) << 6 | bits(inst, 12, 12) << 5 | bits(inst, 6, 5) << 3;
+            encode_i(0x07, rd, 3, SP, uimm as i64)
+        }
+        (0b10, 0b010) => {
+            if rd == 0 {
+                return None;
+            }
+            let uimm = bits(inst, 3, 2) << 6 | bits(inst, 12, 12) << 5 | bits(inst, 6, 4) << 2;
+            encode_i(0x03, rd, 2, SP, uimm as i64)
+        }
+        (0b10, 0b011) => {
+            if is_64 {
+                if rd == 0 {
+                    return None;
+                }
+                let uimm = bits(inst, 4, 2) << 6 | bits(inst, 12, 12) << 5 | bits(inst, 6, 5) << 3;
+                encode_i(0x03, rd, 3, SP, uimm as i64)
+            } else {
+                let uimm = bits(inst, 3, 2) << 6 | bits(inst, 12, 12) << 5 | bits(inst, 6, 4) << 2;
+                encode_i(0x07, rd, 2, SP, uimm as i64)
+            }
+        }
+        (0b10, 0b100) => match (bits(inst, 12, 12), rd, rs2) {
+            (0, 0, _) => return None,
+            (0, _, 0) => encode_i(0x67, 0, 0, rd, 0),
+            (0, _, _) => encode_r(0x33, rd, 0, 0, rs2, 0),
+            (1, 0, 0) => 0x0010_0073,
+            (1, _, 0) => encode_i(0x67, RA, 0, rd, 0),
+            _ => encode_r(0x33, rd, 0, rd, rs2, 0),
+        },
+        (0b10, 0b101) => {
+            let uimm = bits(inst, 9, 7) << 6 | bits(inst, 12, 10) << 3;
+            encode_s(0x27, 3, SP, rs2, uimm as i64)
+        }
+        (0b10, 0b110) => {
+            let uimm = bits(inst, 8, 7) << 6 | bits(inst, 12, 9) << 2;
+            encode_s(0x23, 2, SP, rs2, uimm as i64)
+        }
+        (0b10, 0b111) => {
+            if is_64 {
+                let uimm = bits(inst, 9, 7) << 6 | bits(inst, 12, 10) << 3;
+                encode_s(0x23, 3, SP, rs2, uimm as i64)
+            } else {
+                let uimm = bits(inst, 8, 7) << 6 | bits(inst, 12, 9) << 2;
+                encode_s(0x27, 2, SP, rs2, uimm as i64)
+            }
+        }
+        _ => return None,
+    };
+
+    Some(expanded)
+}
+
+fn compressed_jump_offset(inst: u32) -> i64 {
+    sign_extend(
+        bits(inst, 12, 12) << 11
+            | bits(inst, 8, 8) << 10
+            | bits(inst, 10, 9) << 8
+            | bits(inst, 6, 6) << 7
+            | bits(inst, 7, 7) << 6
+            | bits(inst, 2, 2) << 5
+            | bits(inst, 11, 11) << 4
+            | bits(inst, 5, 3) << 1,
+        12,
+    )
+}
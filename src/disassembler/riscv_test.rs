@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::disassembler::riscv::Decoder;
+    use crate::disassembler::{Disassembler, FlowControl};
+
+    fn decode_all(xlen: u32, bytes: &[u8]) -> Vec<String> {
+        let mut decoder = Decoder::with_ip(xlen, bytes, 0x1000);
+        let mut output = Vec::new();
+        while decoder.can_decode() {
+            output.push(decoder.decode().to_string());
+        }
+        output
+    }
+
+    #[test]
+    fn riscv_base_test() {
+        let bytes = [
+            0x13, 0x01, 0x01, 0xfe, // addi sp,sp,-32
+            0x37, 0x56, 0x34, 0x12, // lui a2,0x12345
+            0x33, 0x05, 0xb6, 0x02, // mul a0,a2,a1
+            0x2f, 0x26, 0xa1, 0x00, // amoadd.w a2,a0,(sp)
+            0x73, 0x25, 0x40, 0xf1, // csrr a0,mhartid
+            0x53, 0x75, 0x25, 0xd2, // fcvt.d.l fa0,a0
+            0x67, 0x80, 0x00, 0x00, // ret
+        ];
+        assert_eq!(
+            decode_all(64, &bytes),
+            vec![
+                "addi      sp,sp,-32",
+                "lui       a2,0x12345",
+                "mul       a0,a2,a1",
+                "amoadd.w  a2,a0,(sp)",
+                "csrr      a0,mhartid",
+                "fcvt.d.l  fa0,a0",
+                "ret",
+            ]
+        );
+    }
+
+    #[test]
+    fn riscv_compressed_test() {
+        let bytes = [
+            0x41, 0x11, // addi sp,sp,-16
+            0x06, 0xe4, // sd ra,8(sp)
+            0x2e, 0x85, // mv a0,a1
+            0xfd, 0xbf, // j 0x1004
+            0x82, 0x80, // ret
+        ];
+        assert_eq!(
+            decode_all(64, &bytes),
+            vec![
+                "addi      sp,sp,-16",
+                "sd        ra,8(sp)",
+                "mv        a0,a1",
+                "j         0x1004",
+                "ret",
+            ]
+        );
+        // c.jal only exists on RV32, RV64 reuses the encoding for c.addiw
+        assert_eq!(decode_all(32, &[0x01, 0x20]), vec!["jal       0x1000"]);
+        assert_eq!(decode_all(64, &[0x05, 0x25]), vec!["addiw     a0,a0,1"]);
+    }
+
+    #[test]
+    fn riscv_call_target_test() {
+        let bytes = [
+            0x97, 0x00, 0x00, 0x00, // auipc ra,0x0
+            0xe7, 0x80, 0xa0, 0x01, // jalr ra,26(ra)
+            0xef, 0x00, 0x00, 0x01, // jal 0x1018
+        ];
+        let mut decoder = Decoder::with_ip(64, &bytes, 0x1000);
+        decoder.decode();
+        let call = decoder.decode();
+        assert_eq!(call.flow_control, FlowControl::Call);
+        assert_eq!(call.target, Some(0x101a));
+        let call = decoder.decode();
+        assert_eq!(call.flow_control, FlowControl::Call);
+        assert_eq!(call.target, Some(0x1018));
+    }
+}
@@ -0,0 +1,82 @@
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    /// index of the section the symbol is defined in, if known
+    section: Option<usize>,
+    addr: u64,
+    size: u64,
+}
+
+/// Address to symbol lookup used to annotate branch targets and labels.
+#[derive(Clone, Default)]
+pub struct SymbolMap {
+    entries: Rc<Vec<Entry>>,
+}
+
+impl SymbolMap {
+    /// `symbols` yields `(name, addr, size)`.
+    pub fn new<'a>(symbols: impl Iterator<Item = (&'a str, u64, u64)>) -> SymbolMap {
+        Self::with_sections(symbols.map(|(name, addr, size)| (name, None, addr, size)))
+    }
+
+    /// `symbols` yields `(name, section index, addr, size)`.
+    pub fn with_sections<'a>(
+        symbols: impl Iterator<Item = (&'a str, Option<usize>, u64, u64)>,
+    ) -> SymbolMap {
+        let mut entries = symbols
+            .filter(|(name, _, _, _)| !name.is_empty())
+            .map(|(name, section, addr, size)| Entry {
+                name: name.to_string(),
+                section,
+                addr,
+                size,
+            })
+            .collect::<Vec<Entry>>();
+        entries.sort_by_key(|e| e.addr);
+
+        SymbolMap {
+            entries: Rc::new(entries),
+        }
+    }
+
+    /// Keeps the symbols of `section` and those without a section. Every section of a
+    /// relocatable object starts at address 0, so an address alone is ambiguous there.
+    pub fn section(&self, section: usize) -> SymbolMap {
+        SymbolMap {
+            entries: Rc::new(
+                self.entries
+                    .iter()
+                    .filter(|e| e.section.is_none_or(|s| s == section))
+                    .cloned()
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the symbol containing `addr` and its start address.
+    pub fn lookup(&self, addr: u64) -> Option<(&str, u64)> {
+        let end = self.entries.partition_point(|e| e.addr <= addr);
+        self.entries[..end]
+            .iter()
+            .rev()
+            .find(|e| e.addr == addr || addr - e.addr < e.size)
+            .map(|e| (e.name.as_str(), e.addr))
+    }
+
+    /// Returns the symbol starting exactly at `addr`.
+    pub fn label(&self, addr: u64) -> Option<&str> {
+        self.lookup(addr)
+            .filter(|(_, start)| *start == addr)
+            .map(|(name, _)| name)
+    }
+
+    /// Formats `addr` as `symbol` or `symbol+0xoffset`.
+    pub fn resolve(&self, addr: u64) -> Option<String> {
+        self.lookup(addr).map(|(name, start)| match addr - start {
+            0 => name.to_string(),
+            offset => format!("{name}+{offset:#x}"),
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use super::symbol::SymbolMap;
+use super::{Disassembler, FlowControl, FormatOptions, HexStyle, Instruction, Syntax};
+use iced_x86::{
+    DecoderOptions, Formatter, GasFormatter, IntelFormatter, MasmFormatter, NasmFormatter, OpKind,
+    Register, SymbolResolver, SymbolResult,
+};
+
+pub struct Decoder<'a> {
+    decoder: iced_x86::Decoder<'a>,
+    formatter: Box<dyn Formatter>,
+    bytes: &'a [u8],
+    ip: u64,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn with_options(
+        bitness: u32,
+        bytes: &'a [u8],
+        ip: u64,
+        options: &FormatOptions,
+        symbols: SymbolMap,
+    ) -> Decoder<'a> {
+        let resolver: Option<Box<dyn SymbolResolver>> = Some(Box::new(Resolver(symbols)));
+        let mut formatter: Box<dyn Formatter> = match options.syntax {
+            Syntax::Att => Box::new(GasFormatter::with_options(resolver, None)),
+            Syntax::Intel => Box::new(IntelFormatter::with_options(resolver, None)),
+            Syntax::Masm => Box::new(MasmFormatter::with_options(resolver, None)),
+            Syntax::Nasm => Box::new(NasmFormatter::with_options(resolver, None)),
+        };
+        formatter
+            .options_mut()
+            .set_uppercase_mnemonics(options.uppercase);
+        match options.hex_style {
+            Some(HexStyle::Prefix) => {
+                formatter.options_mut().set_hex_prefix("0x");
+                formatter.options_mut().set_hex_suffix("");
+            }
+            Some(HexStyle::Suffix) => {
+                formatter.options_mut().set_hex_prefix("");
+                formatter.options_mut().set_hex_suffix("h");
+            }
+            None => (),
+        }
+        if options.digit_separator {
+            formatter.options_mut().set_digit_separator("_");
+        }
+
+        Decoder {
+            decoder: iced_x86::Decoder::with_ip(bitness, bytes, ip, DecoderOptions::NONE),
+            formatter,
+            bytes,
+            ip,
+        }
+    }
+}
+
+impl Disassembler for Decoder<'_> {
+    fn can_decode(&self) -> bool {
+        self.decoder.can_decode()
+    }
+
+    fn decode(&mut self) -> Instruction {
+        let instruction = self.decoder.decode();
+
+        let mut mnemonic = String::new();
+        let mut operands = String::new();
+        self.formatter.format_mnemonic(&instruction, &mut mnemonic);
+        self.formatter
+            .format_all_operands(&instruction, &mut operands);
+
+        let start_index = (instruction.ip() - self.ip) as usize;
+        let (flow_control, target) = classify(&instruction);
+
+        Instruction {
+            ip: instruction.ip(),
+            bytes: self.bytes[start_index..start_index + instruction.len()].to_vec(),
+            mnemonic,
+            base_mnemonic: format!("{:?}", instruction.mnemonic()).to_lowercase(),
+            operands,
+            flow_control,
+            target,
+        }
+    }
+}
+
+struct Resolver(SymbolMap);
+
+impl SymbolResolver for Resolver {
+    fn symbol(
+        &mut self,
+        instruction: &iced_x86::Instruction,
+        _operand: u32,
+        instruction_operand: Option<u32>,
+        address: u64,
+        _address_size: u32,
+    ) -> Option<SymbolResult<'_>> {
+        // only branch targets and absolute/RIP-relative memory operands are addresses
+        let is_address = match instruction_operand.map(|op| instruction.op_kind(op)) {
+            Some(OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64) => true,
+            Some(OpKind::FarBranch16 | OpKind::FarBranch32) => true,
+            Some(OpKind::Memory) => {
+                matches!(
+                    instruction.memory_base(),
+                    Register::None | Register::RIP | Register::EIP
+                ) && instruction.memory_index() == Register::None
+            }
+            _ => false,
+        };
+        if !is_address {
+            return None;
+        }
+
+        self.0
+            .lookup(address)
+            .map(|(name, start)| SymbolResult::with_str(start, name))
+    }
+}
+
+fn classify(instruction: &iced_x86::Instruction) -> (FlowControl, Option<u64>) {
+    let flow_control = match instruction.flow_control() {
+        iced_x86::FlowControl::Next => FlowControl::Next,
+        iced_x86::FlowControl::UnconditionalBranch => FlowControl::UnconditionalBranch,
+        iced_x86::FlowControl::ConditionalBranch | iced_x86::FlowControl::XbeginXabortXend => {
+            FlowControl::ConditionalBranch
+        }
+        iced_x86::FlowControl::IndirectBranch => FlowControl::IndirectBranch,
+        iced_x86::FlowControl::Call => FlowControl::Call,
+        iced_x86::FlowControl::IndirectCall => FlowControl::IndirectCall,
+        iced_x86::FlowControl::Return => FlowControl::Return,
+        iced_x86::FlowControl::Interrupt | iced_x86::FlowControl::Exception => {
+            FlowControl::Exception
+        }
+    };
+
+    let target = match instruction.op0_kind() {
+        OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+            Some(instruction.near_branch_target())
+        }
+        OpKind::FarBranch16 | OpKind::FarBranch32 => Some(instruction.far_branch32() as u64),
+        // `call [rip+disp]` / `jmp [disp]` go through a pointer at a fixed address
+        OpKind::Memory
+            if matches!(
+                flow_control,
+                FlowControl::IndirectCall | FlowControl::IndirectBranch
+            ) && matches!(
+                instruction.memory_base(),
+                Register::None | Register::RIP | Register::EIP
+            ) && instruction.memory_index() == Register::None =>
+        {
+            Some(instruction.memory_displacement64())
+        }
+        _ => None,
+    };
+
+    (flow_control, target)
+}
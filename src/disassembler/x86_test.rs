@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use crate::disassembler::symbol::SymbolMap;
+    use crate::disassembler::x86::Decoder;
+    use crate::disassembler::{Disassembler, FlowControl, FormatOptions, HexStyle, Syntax};
+
+    #[test]
+    fn x86_64_test() {
+        let bytes = [
+            0x48, 0x89, 0xe5, // mov rbp,rsp
+            0xe8, 0x10, 0x00, 0x00, 0x00, // call 1018h
+            0xff, 0x15, 0x00, 0x20, 0x00, 0x00, // call qword [rel 300Eh]
+            0x74, 0xf0, // je 1000h
+            0xc3, // ret
+        ];
+        let mut decoder = Decoder::with_options(
+            64,
+            &bytes,
+            0x1000,
+            &FormatOptions::default(),
+            SymbolMap::default(),
+        );
+        let mut output = Vec::new();
+        while decoder.can_decode() {
+            output.push(decoder.decode());
+        }
+
+        assert_eq!(output[0].to_string(), "mov       rbp,rsp");
+        assert_eq!(output[0].bytes, vec![0x48, 0x89, 0xe5]);
+        assert_eq!(output[1].flow_control, FlowControl::Call);
+        assert_eq!(output[1].target, Some(0x1018));
+        assert_eq!(output[2].flow_control, FlowControl::IndirectCall);
+        assert_eq!(output[2].target, Some(0x300e));
+        assert_eq!(output[3].flow_control, FlowControl::ConditionalBranch);
+        assert_eq!(output[3].target, Some(0x1000));
+        assert_eq!(output[4].flow_control, FlowControl::Return);
+    }
+
+    #[test]
+    fn x86_syntax_test() {
+        let bytes = [0x48, 0x83, 0xe4, 0xf0]; // and rsp,0FFFFFFFFFFFFFFF0h
+        let format = |options: FormatOptions| {
+            Decoder::with_options(64, &bytes, 0x1000, &options, SymbolMap::default())
+                .decode()
+                .to_string()
+        };
+
+        assert_eq!(
+            format(FormatOptions::default()),
+            "and       rsp,0FFFFFFFFFFFFFFF0h"
+        );
+        assert_eq!(
+            format(FormatOptions {
+                syntax: Syntax::Att,
+                ..FormatOptions::default()
+            }),
+            "and       $0xFFFFFFFFFFFFFFF0,%rsp"
+        );
+        assert_eq!(
+            format(FormatOptions {
+                syntax: Syntax::Masm,
+                uppercase: true,
+                hex_style: Some(HexStyle::Prefix),
+                digit_separator: true,
+            }),
+            "AND       rsp,0xFFFF_FFFF_FFFF_FFF0"
+        );
+    }
+
+    #[test]
+    fn x86_symbol_test() {
+        let bytes = [
+            0xe8, 0x10, 0x00, 0x00, 0x00, // call 1015h
+            0x48, 0x8d, 0x3d, 0x04, 0x00, 0x00, 0x00, // lea rdi,[rel 1010h]
+            0xb8, 0x15, 0x10, 0x00, 0x00, // mov eax,1015h
+        ];
+        let symbols = SymbolMap::new(
+            [("main", 0x1000, 0x15), ("helper", 0x1015, 0x10)]
+                .iter()
+                .map(|(name, addr, size)| (*name, *addr, *size)),
+        );
+        let mut decoder =
+            Decoder::with_options(64, &bytes, 0x1000, &FormatOptions::default(), symbols);
+
+        assert_eq!(decoder.decode().to_string(), "call      helper");
+        assert_eq!(decoder.decode().to_string(), "lea       rdi,[rel main+10h]");
+        assert_eq!(decoder.decode().to_string(), "mov       eax,1015h");
+    }
+
+    #[test]
+    fn x86_section_symbol_test() {
+        // two sections of a relocatable object, both starting at address 0
+        let bytes = [0xe8, 0xfb, 0xff, 0xff, 0xff]; // call 0
+        let symbols = SymbolMap::with_sections(
+            [("main", 1, 0, 6), ("cold", 2, 0, 2)]
+                .iter()
+                .map(|(name, section, addr, size)| (*name, Some(*section), *addr, *size)),
+        );
+
+        assert_eq!(symbols.section(1).label(0), Some("main"));
+        assert_eq!(symbols.section(2).label(0), Some("cold"));
+        assert_eq!(symbols.section(3).label(0), None);
+
+        let mut decoder =
+            Decoder::with_options(64, &bytes, 0, &FormatOptions::default(), symbols.section(2));
+        assert_eq!(decoder.decode().to_string(), "call      cold");
+    }
+
+    #[test]
+    fn x86_base_mnemonic_test() {
+        let bytes = [
+            0xff, 0x15, 0x00, 0x00, 0x00, 0x00, // call qword [rel 1006h]
+            0x48, 0xc7, 0x00, 0x01, 0x00, 0x00, 0x00, // mov qword [rax],1
+        ];
+        let decode = |options: FormatOptions| {
+            let mut decoder =
+                Decoder::with_options(64, &bytes, 0x1000, &options, SymbolMap::default());
+            let mut output = Vec::new();
+            while decoder.can_decode() {
+                output.push(decoder.decode());
+            }
+            output
+        };
+
+        // AT&T adds operand size suffixes, the base mnemonic stays the same
+        let att = decode(FormatOptions {
+            syntax: Syntax::Att,
+            uppercase: true,
+            ..FormatOptions::default()
+        });
+        let nasm = decode(FormatOptions::default());
+        assert_eq!(att[0].mnemonic, "CALLQ");
+        assert_eq!(att[1].mnemonic, "MOVQ");
+        assert_eq!(nasm[0].mnemonic, "call");
+        assert_eq!(nasm[1].mnemonic, "mov");
+        for (att, nasm) in att.iter().zip(nasm.iter()) {
+            assert_eq!(att.base_mnemonic, nasm.base_mnemonic);
+        }
+        assert_eq!(att[0].base_mnemonic, "call");
+        assert_eq!(att[1].base_mnemonic, "mov");
+    }
+}
@@ -4,6 +4,8 @@ mod function;
 pub mod pe;
 mod pe_test;
 
+use crate::disassembler::FormatOptions;
+
 #[allow(clippy::identity_op)]
 pub fn get_u16(mmap: &[u8], index: usize) -> u16 {
     (mmap[index + 1] as u16) << 8 | (mmap[index + 0] as u16)
@@ -29,17 +31,126 @@ pub fn get_u64(mmap: &[u8], index: usize) -> u64 {
         | (mmap[index + 0] as u64)
 }
 
+#[allow(clippy::identity_op)]
+pub fn get_u16_be(mmap: &[u8], index: usize) -> u16 {
+    (mmap[index + 0] as u16) << 8 | (mmap[index + 1] as u16)
+}
+
+#[allow(clippy::identity_op)]
+pub fn get_u32_be(mmap: &[u8], index: usize) -> u32 {
+    (mmap[index + 0] as u32) << 24
+        | (mmap[index + 1] as u32) << 16
+        | (mmap[index + 2] as u32) << 8
+        | (mmap[index + 3] as u32)
+}
+
+#[allow(clippy::identity_op)]
+pub fn get_u64_be(mmap: &[u8], index: usize) -> u64 {
+    (mmap[index + 0] as u64) << 56
+        | (mmap[index + 1] as u64) << 48
+        | (mmap[index + 2] as u64) << 40
+        | (mmap[index + 3] as u64) << 32
+        | (mmap[index + 4] as u64) << 24
+        | (mmap[index + 5] as u64) << 16
+        | (mmap[index + 6] as u64) << 8
+        | (mmap[index + 7] as u64)
+}
+
+pub fn get_cstr(mmap: &[u8], index: usize) -> String {
+    mmap[index..]
+        .iter()
+        .take_while(|c| **c != b'\0')
+        .map(|c| *c as char)
+        .collect::<String>()
+}
+
+#[derive(Clone, Copy)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub fn get_u16(&self, mmap: &[u8], index: usize) -> u16 {
+        match self {
+            Endian::Little => get_u16(mmap, index),
+            Endian::Big => get_u16_be(mmap, index),
+        }
+    }
+
+    pub fn get_u32(&self, mmap: &[u8], index: usize) -> u32 {
+        match self {
+            Endian::Little => get_u32(mmap, index),
+            Endian::Big => get_u32_be(mmap, index),
+        }
+    }
+
+    pub fn get_u64(&self, mmap: &[u8], index: usize) -> u64 {
+        match self {
+            Endian::Little => get_u64(mmap, index),
+            Endian::Big => get_u64_be(mmap, index),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum Arch {
     Bit32,
     Bit64,
 }
 
 pub trait Loader {
-    fn mem_data(&self) -> &[u8];
     fn header_show(&self);
     fn show_segment(&self);
     fn show_section(&self);
-    fn disassemble(&self);
+    fn disassemble(&self, options: &FormatOptions);
     fn show_all_header(&self);
-    fn analysis(&self);
+    fn analysis(&self, options: &FormatOptions);
+    fn show_dynamic(&self) {
+        println!("dynamic section is not supported for this format");
+    }
+    fn show_dynamic_symbols(&self) {
+        println!("dynamic symbols are not supported for this format");
+    }
+    fn show_relocations(&self) {
+        println!("relocations are not supported for this format");
+    }
+    fn show_imports(&self) {
+        println!("imports are not supported for this format");
+    }
+    fn show_exports(&self) {
+        println!("exports are not supported for this format");
+    }
+    fn show_symbols(&self) {
+        println!("symbol table is not supported for this format");
+    }
+    fn show_notes(&self) {
+        println!("notes are not supported for this format");
+    }
+    fn show_security(&self) {
+        println!("security report is not supported for this format");
+    }
+    fn show_debug_directory(&self) {
+        println!("debug directory is not supported for this format");
+    }
+    fn show_tls(&self) {
+        println!("TLS directory is not supported for this format");
+    }
+    fn show_resources(&self) {
+        println!("resources are not supported for this format");
+    }
+    /// Writes the raw bytes of the resource at `path` (`type/name/language`) to `output`.
+    fn save_resource(&self, _path: &str, _output: &str) -> std::io::Result<()> {
+        println!("resources are not supported for this format");
+        Ok(())
+    }
+    /// GNU build-id or PDB key that identifies the matching debug file
+    fn build_id(&self) -> Option<String> {
+        None
+    }
+    /// Copy of the file with base relocations applied as if loaded at `load_base`,
+    /// `None` if the format has no base relocations.
+    fn relocate(&self, _load_base: u64) -> Option<Vec<u8>> {
+        None
+    }
 }
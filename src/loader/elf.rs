@@ -1,8 +1,18 @@
+pub mod dynamic;
 mod elf_32;
 mod elf_64;
-
+pub mod note;
+mod plt;
+pub mod relocation;
+pub mod security;
+pub mod symbol;
+
+use crate::disassembler::arm::{ArmState, MappingSymbol};
+use crate::disassembler::symbol::SymbolMap;
+use crate::disassembler::{FormatOptions, Isa};
 use crate::loader::function::Function;
-use crate::loader::{get_u32, get_u64, Arch, Loader};
+use crate::loader::{get_cstr, Arch, Endian, Loader};
+use dynamic::DynamicEntry;
 use elf_32::elf_header::ElfHeader32;
 use elf_32::program_header::ProgramHeader32;
 use elf_32::section_header::SectionHeader32;
@@ -10,7 +20,11 @@ use elf_64::elf_header::ElfHeader64;
 use elf_64::program_header::ProgramHeader64;
 use elf_64::section_header::SectionHeader64;
 use memmap::Mmap;
+use note::{Note, NoteSection};
+use relocation::{Relocation, RelocationTable};
+use security::SecurityReport;
 use std::collections::HashMap;
+use symbol::Symbol;
 
 pub struct ElfIdentification {
     magic: [u8; 16],
@@ -47,6 +61,46 @@ impl ElfIdentification {
         }
     }
 
+    fn get_endian(&self) -> Endian {
+        const EI_DATA: usize = 5;
+        if self.magic[EI_DATA] == 2 {
+            Endian::Big
+        } else {
+            Endian::Little
+        }
+    }
+
+    fn get_isa(&self, e_machine: u16, e_entry: u64, e_flags: u32) -> Isa {
+        const EF_ARM_BE8: u32 = 0x0080_0000;
+        const EM_386: u16 = 3;
+        const EM_ARM: u16 = 40;
+        const EM_X86_64: u16 = 62;
+        const EM_AARCH64: u16 = 183;
+        const EM_RISCV: u16 = 243;
+        let bitness = match self.get_arch() {
+            Arch::Bit32 => 32,
+            Arch::Bit64 => 64,
+        };
+        match e_machine {
+            EM_386 => Isa::X86(32),
+            EM_X86_64 => Isa::X86(64),
+            EM_RISCV => Isa::RiscV(bitness),
+            EM_AARCH64 => Isa::AArch64,
+            EM_ARM => {
+                // BE8 images keep little-endian instructions, only BE32 code is byte swapped
+                let endian = match self.get_endian() {
+                    Endian::Big if e_flags & EF_ARM_BE8 == 0 => Endian::Big,
+                    _ => Endian::Little,
+                };
+                match e_entry & 1 {
+                    1 => Isa::Arm(ArmState::Thumb, endian),
+                    _ => Isa::Arm(ArmState::Arm, endian),
+                }
+            }
+            _ => Isa::X86(bitness),
+        }
+    }
+
     fn show(&self) {
         print!("magic:\t");
         for byte in self.magic.iter() {
@@ -66,131 +120,408 @@ pub struct ElfLoader {
     pub prog_headers: Vec<Box<dyn ProgramHeader>>,
     pub sect_headers: Vec<Box<dyn SectionHeader>>,
     pub functions: Vec<Function>,
+    pub mapping_symbols: HashMap<usize, Vec<MappingSymbol>>,
+    pub dynamic: Vec<DynamicEntry>,
+    pub dynamic_symbols: Vec<Symbol>,
+    pub relocations: Vec<RelocationTable>,
+    pub plt: Vec<Function>,
+    pub notes: Vec<NoteSection>,
     pub mem_data: Mmap,
 }
 
 impl ElfLoader {
-    fn addr2offset(prog_headers: &[Box<dyn ProgramHeader>], addr: u64) -> Option<u64> {
-        let mut addr_table = Vec::new();
-        for seg in prog_headers {
-            addr_table.push(seg.offset_and_addr());
+    fn create_func_table(
+        symbols: &[Symbol],
+        isa: Isa,
+        sect_headers: &[Box<dyn SectionHeader>],
+    ) -> Vec<Function> {
+        let mut functions: Vec<Function> = Vec::new();
+        for sym in symbols
+            .iter()
+            .filter(|sym| sym.info & 0xf == symbol::STT_FUNC)
+        {
+            // skip undefined symbols and special section indexes (SHN_ABS, SHN_COMMON, ...)
+            let sect = match sect_headers.get(sym.shndx as usize) {
+                Some(sect) if sym.shndx != 0 => sect,
+                _ => continue,
+            };
+
+            // the lowest bit of a Thumb function address is the interworking bit
+            let addr = match isa {
+                Isa::Arm(..) => sym.value & !1,
+                _ => sym.value,
+            };
+            // a symbol outside its section has no place in the file
+            let offset = match addr.checked_sub(sect.sh_addr()) {
+                Some(delta) => sect.sh_offset() + delta,
+                None => continue,
+            };
+            functions.push(Function {
+                name: sym.versioned_name(),
+                addr,
+                offset,
+                size: sym.size,
+            });
         }
 
-        addr_table.sort_by(|x, y| (x.1).cmp(&y.1));
-        for w in addr_table.windows(2) {
-            let (a, z) = (w[0], w[1]);
-            if a.1 <= addr && addr < z.1 {
-                return Some(a.0 + (addr - a.1));
-            }
+        functions
+    }
+
+    fn create_mapping_table(symbols: &[Symbol], isa: Isa) -> HashMap<usize, Vec<MappingSymbol>> {
+        let mut mapping_symbols: HashMap<usize, Vec<MappingSymbol>> = HashMap::new();
+        if !matches!(isa, Isa::Arm(..) | Isa::AArch64) {
+            return mapping_symbols;
         }
 
-        None
+        for sym in symbols.iter().filter(|sym| sym.shndx != 0) {
+            let mapping = match MappingSymbol::from_name(&sym.name, sym.value) {
+                Some(mapping) => mapping,
+                None if matches!(isa, Isa::Arm(..)) && sym.info & 0xf == symbol::STT_FUNC => {
+                    MappingSymbol {
+                        addr: sym.value & !1,
+                        state: if sym.value & 1 == 1 {
+                            ArmState::Thumb
+                        } else {
+                            ArmState::Arm
+                        },
+                    }
+                }
+                None => continue,
+            };
+            mapping_symbols
+                .entry(sym.shndx as usize)
+                .or_default()
+                .push(mapping);
+        }
+
+        mapping_symbols
     }
 
-    fn create_func_table(
+    fn addr_to_offset(prog_headers: &[Box<dyn ProgramHeader>], addr: u64) -> Option<u64> {
+        const PT_LOAD: u32 = 1;
+        prog_headers
+            .iter()
+            .filter(|p| p.p_type() == PT_LOAD)
+            .find(|p| (p.p_vaddr()..p.p_vaddr() + p.p_filesz()).contains(&addr))
+            .map(|p| p.p_offset() + (addr - p.p_vaddr()))
+    }
+
+    fn create_dynamic_table(
         mmap: &[u8],
         arch: Arch,
+        endian: Endian,
         prog_headers: &[Box<dyn ProgramHeader>],
         sect_headers: &[Box<dyn SectionHeader>],
-    ) -> Vec<Function> {
-        let symtab = sect_headers.iter().find(|s| s.sh_name() == ".symtab");
-        let strtab = sect_headers.iter().find(|s| s.sh_name() == ".strtab");
-
-        let st_size: usize = match arch {
-            Arch::Bit32 => 16,
-            Arch::Bit64 => 24,
+    ) -> Vec<DynamicEntry> {
+        const PT_DYNAMIC: u32 = 2;
+        const SHT_DYNAMIC: u32 = 6;
+        let table = match prog_headers.iter().find(|p| p.p_type() == PT_DYNAMIC) {
+            Some(p) => p.p_offset()..p.p_offset() + p.p_filesz(),
+            None => match sect_headers.iter().find(|s| s.sh_type() == SHT_DYNAMIC) {
+                Some(s) => s.section_range(),
+                None => return Vec::new(),
+            },
         };
-        let mut functions: Vec<Function> = Vec::new();
-        if let (Some(symtab), Some(strtab)) = (symtab, strtab) {
-            for symtab_off in symtab.section_range().step_by(st_size) {
-                let st_info = mmap[symtab_off as usize + 4];
-                if st_info & 0xf == 2 {
-                    let st_name_off = get_u32(mmap, symtab_off as usize);
-                    let st_name = mmap[(strtab.sh_offset() + st_name_off as u64) as usize..]
-                        .iter()
-                        .take_while(|c| **c as char != '\0')
-                        .map(|c| *c as char)
-                        .collect::<String>();
-                    let st_addr = get_u64(mmap, (symtab_off + 8) as usize);
-                    let st_size = get_u64(mmap, (symtab_off + 16) as usize);
-
-                    functions.push(Function {
-                        name: st_name,
-                        addr: Self::addr2offset(prog_headers, st_addr).unwrap(),
-                        size: st_size,
-                    });
-                }
+        let entry_size: usize = match arch {
+            Arch::Bit32 => 8,
+            Arch::Bit64 => 16,
+        };
+
+        let mut dynamic: Vec<DynamicEntry> = Vec::new();
+        for entry_off in table.step_by(entry_size) {
+            let entry = DynamicEntry::new(mmap, arch, endian, entry_off as usize);
+            let is_null = entry.d_tag == dynamic::DT_NULL;
+            dynamic.push(entry);
+            if is_null {
+                break;
             }
         }
 
-        functions
+        let strtab = dynamic
+            .iter()
+            .find(|e| e.d_tag == dynamic::DT_STRTAB)
+            .and_then(|e| Self::addr_to_offset(prog_headers, e.d_val));
+        if let Some(strtab) = strtab {
+            for entry in dynamic.iter_mut().filter(|e| e.has_string()) {
+                entry.name = Some(get_cstr(mmap, (strtab + entry.d_val) as usize));
+            }
+        }
+
+        dynamic
+    }
+
+    fn create_relocation_tables(
+        mmap: &[u8],
+        arch: Arch,
+        endian: Endian,
+        e_machine: u16,
+        sect_headers: &[Box<dyn SectionHeader>],
+        symbols: &[Symbol],
+        dynamic_symbols: &[Symbol],
+    ) -> Vec<RelocationTable> {
+        const SHT_SYMTAB: u32 = 2;
+        const SHT_RELA: u32 = 4;
+        const SHT_REL: u32 = 9;
+        const SHT_DYNSYM: u32 = 11;
+        const STT_SECTION: u8 = 3;
+
+        let mut tables: Vec<RelocationTable> = Vec::new();
+        for sect in sect_headers
+            .iter()
+            .filter(|s| s.sh_type() == SHT_REL || s.sh_type() == SHT_RELA)
+        {
+            let is_rela = sect.sh_type() == SHT_RELA;
+            let entry_size: usize = match (arch, is_rela) {
+                (Arch::Bit32, false) => 8,
+                (Arch::Bit32, true) => 12,
+                (Arch::Bit64, false) => 16,
+                (Arch::Bit64, true) => 24,
+            };
+            let symbols = match sect_headers
+                .get(sect.sh_link() as usize)
+                .map(|s| s.sh_type())
+            {
+                Some(SHT_DYNSYM) => dynamic_symbols,
+                Some(SHT_SYMTAB) => symbols,
+                _ => &[],
+            };
+
+            let relocations = sect
+                .section_range()
+                .step_by(entry_size)
+                .map(|entry_off| {
+                    let mut reloc =
+                        Relocation::new(mmap, arch, endian, entry_off as usize, is_rela, symbols);
+                    // section symbols have no name of their own
+                    if let Some(sym) = symbols
+                        .get(reloc.r_sym as usize)
+                        .filter(|s| reloc.r_sym != 0 && s.info & 0xf == STT_SECTION)
+                    {
+                        reloc.symbol = sect_headers
+                            .get(sym.shndx as usize)
+                            .map(|s| s.sh_name().to_string());
+                    }
+                    reloc
+                })
+                .collect::<Vec<Relocation>>();
+
+            tables.push(RelocationTable {
+                name: sect.sh_name().to_string(),
+                offset: sect.sh_offset(),
+                e_machine,
+                relocations,
+            });
+        }
+
+        tables
+    }
+
+    fn create_note_sections(
+        mmap: &[u8],
+        arch: Arch,
+        endian: Endian,
+        e_machine: u16,
+        prog_headers: &[Box<dyn ProgramHeader>],
+        sect_headers: &[Box<dyn SectionHeader>],
+    ) -> Vec<NoteSection> {
+        const PT_NOTE: u32 = 4;
+        const SHT_NOTE: u32 = 7;
+        let mut note_sections: Vec<NoteSection> = sect_headers
+            .iter()
+            .filter(|s| s.sh_type() == SHT_NOTE)
+            .map(|s| NoteSection {
+                name: s.sh_name().to_string(),
+                offset: s.sh_offset(),
+                e_machine,
+                notes: Note::read_all(mmap, arch, endian, s.section_range(), s.sh_addralign()),
+            })
+            .collect();
+
+        // files without section headers still carry their notes in PT_NOTE segments
+        if note_sections.is_empty() {
+            note_sections = prog_headers
+                .iter()
+                .filter(|p| p.p_type() == PT_NOTE)
+                .map(|p| NoteSection {
+                    name: "PT_NOTE".to_string(),
+                    offset: p.p_offset(),
+                    e_machine,
+                    notes: Note::read_all(
+                        mmap,
+                        arch,
+                        endian,
+                        p.p_offset()..p.p_offset() + p.p_filesz(),
+                        p.p_align(),
+                    ),
+                })
+                .collect();
+        }
+
+        note_sections
+    }
+
+    /// Index of the section holding file offset `offset`.
+    pub fn section_index(&self, offset: u64) -> Option<usize> {
+        const SHT_NOBITS: u32 = 8;
+        self.sect_headers
+            .iter()
+            .position(|s| s.sh_type() != SHT_NOBITS && s.section_range().contains(&offset))
+    }
+
+    /// Symbols of relocatable objects are keyed by section, since all their sections start
+    /// at address 0. Linked files have distinct addresses, so any section can be referenced.
+    pub fn symbol_map(&self) -> SymbolMap {
+        const ET_REL: u16 = 1;
+        let relocatable = self.elf_header.e_type() == ET_REL;
+        SymbolMap::with_sections(self.functions.iter().chain(self.plt.iter()).map(|f| {
+            (
+                f.name.as_str(),
+                self.section_index(f.offset).filter(|_| relocatable),
+                f.addr,
+                f.size,
+            )
+        }))
+    }
+
+    pub fn security_report(&self) -> SecurityReport {
+        SecurityReport::new(self)
     }
 
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(mapped_data: Mmap) -> Box<dyn Loader> {
+        Box::new(Self::load(mapped_data))
+    }
+
+    pub fn load(mapped_data: Mmap) -> ElfLoader {
         let elf_ident = ElfIdentification::new(&mapped_data);
-        match elf_ident.get_arch() {
+        let arch = elf_ident.get_arch();
+        let endian = elf_ident.get_endian();
+        let (new_elf, new_prog, new_sect): (Box<dyn ElfHeader>, _, _) = match arch {
             Arch::Bit32 => {
                 let new_elf = ElfHeader32::new(&mapped_data, elf_ident);
                 let new_prog = ProgramHeader32::new(&mapped_data, &new_elf);
                 let new_sect = SectionHeader32::new(&mapped_data, &new_elf);
-                let new_func =
-                    Self::create_func_table(&mapped_data, Arch::Bit32, &new_prog, &new_sect);
-
-                Box::new(ElfLoader {
-                    elf_header: new_elf,
-                    prog_headers: new_prog,
-                    sect_headers: new_sect,
-                    functions: new_func,
-                    mem_data: mapped_data,
-                })
+                (new_elf, new_prog, new_sect)
             }
             Arch::Bit64 => {
                 let new_elf = ElfHeader64::new(&mapped_data, elf_ident);
                 let new_prog = ProgramHeader64::new(&mapped_data, &new_elf);
                 let new_sect = SectionHeader64::new(&mapped_data, &new_elf);
-                let new_func =
-                    Self::create_func_table(&mapped_data, Arch::Bit64, &new_prog, &new_sect);
-
-                Box::new(ElfLoader {
-                    elf_header: new_elf,
-                    prog_headers: new_prog,
-                    sect_headers: new_sect,
-                    functions: new_func,
-                    mem_data: mapped_data,
-                })
+                (new_elf, new_prog, new_sect)
+            }
+        };
+        let symtab = new_sect.iter().find(|s| s.sh_name() == ".symtab");
+        let strtab = new_sect.iter().find(|s| s.sh_name() == ".strtab");
+        let symbols = match (symtab, strtab) {
+            (Some(symtab), Some(strtab)) => {
+                symbol::read_symbols(&mapped_data, arch, endian, symtab.as_ref(), strtab.as_ref())
             }
+            _ => Vec::new(),
+        };
+        let new_dynsym = symbol::read_dynamic_symbols(&mapped_data, arch, endian, &new_sect);
+        // stripped binaries only have the dynamic symbols left
+        let new_func = if symbols.is_empty() {
+            Self::create_func_table(&new_dynsym, new_elf.isa(), &new_sect)
+        } else {
+            Self::create_func_table(&symbols, new_elf.isa(), &new_sect)
+        };
+        let new_mapping = Self::create_mapping_table(&symbols, new_elf.isa());
+        let new_dynamic =
+            Self::create_dynamic_table(&mapped_data, arch, endian, &new_prog, &new_sect);
+        let new_reloc = Self::create_relocation_tables(
+            &mapped_data,
+            arch,
+            endian,
+            new_elf.e_machine(),
+            &new_sect,
+            &symbols,
+            &new_dynsym,
+        );
+        let new_plt = plt::create_plt_table(
+            &mapped_data,
+            new_elf.e_machine(),
+            new_elf.isa(),
+            &new_sect,
+            &new_dynamic,
+            &new_reloc,
+            &new_dynsym,
+        );
+
+        let new_notes = Self::create_note_sections(
+            &mapped_data,
+            arch,
+            endian,
+            new_elf.e_machine(),
+            &new_prog,
+            &new_sect,
+        );
+
+        ElfLoader {
+            elf_header: new_elf,
+            prog_headers: new_prog,
+            sect_headers: new_sect,
+            functions: new_func,
+            mapping_symbols: new_mapping,
+            dynamic: new_dynamic,
+            dynamic_symbols: new_dynsym,
+            relocations: new_reloc,
+            plt: new_plt,
+            notes: new_notes,
+            mem_data: mapped_data,
         }
     }
 }
 
 pub trait ElfHeader {
+    fn e_type(&self) -> u16;
+    fn e_machine(&self) -> u16;
     fn show(&self);
+    fn isa(&self) -> Isa;
 }
 
 pub trait ProgramHeader {
+    fn p_type(&self) -> u32;
+    fn p_flags(&self) -> u32;
+    fn p_offset(&self) -> u64;
+    fn p_vaddr(&self) -> u64;
+    fn p_filesz(&self) -> u64;
+    fn p_align(&self) -> u64;
     fn show(&self, id: usize);
+    #[allow(dead_code)]
     fn dump(&self, mmap: &[u8]);
-    fn offset_and_addr(&self) -> (u64, u64);
 }
 
 pub trait SectionHeader {
-    fn get_sh_name(mmap: &[u8], section_head: usize, name_table_head: usize) -> String
+    fn get_sh_name(
+        mmap: &[u8],
+        endian: Endian,
+        section_head: usize,
+        name_table_head: usize,
+    ) -> String
     where
         Self: Sized;
     fn sh_name(&self) -> &str;
+    fn sh_type(&self) -> u32;
+    fn sh_link(&self) -> u32;
+    fn sh_addr(&self) -> u64;
     fn sh_offset(&self) -> u64;
+    fn sh_size(&self) -> u64;
+    fn sh_addralign(&self) -> u64;
+    fn sh_entsize(&self) -> u64;
     fn section_range(&self) -> std::ops::Range<u64>;
     fn type_to_str(&self) -> &'static str;
     fn show(&self, id: usize);
-    fn dump(&self, mmap: &[u8]);
-    fn inst_analysis(&self, inst_list: &mut HashMap<String, u32>, mmap: &[u8]);
+    fn dump(
+        &self,
+        mmap: &[u8],
+        isa: Isa,
+        mapping_symbols: &[MappingSymbol],
+        options: &FormatOptions,
+        symbols: &SymbolMap,
+    );
 }
 
 impl Loader for ElfLoader {
-    fn mem_data(&self) -> &[u8] {
-        &self.mem_data
-    }
-
     fn header_show(&self) {
         self.elf_header.show();
     }
@@ -209,14 +540,70 @@ impl Loader for ElfLoader {
         }
     }
 
-    fn disassemble(&self) {
+    fn disassemble(&self, options: &FormatOptions) {
+        let symbols = self.symbol_map();
         for (id, sect) in self.sect_headers.iter().enumerate() {
             sect.show(id);
-            sect.dump(&self.mem_data);
+            let mapping_symbols = self.mapping_symbols.get(&id).map_or(&[][..], Vec::as_slice);
+            sect.dump(
+                &self.mem_data,
+                self.elf_header.isa(),
+                mapping_symbols,
+                options,
+                &symbols.section(id),
+            );
             println!("\n\n");
         }
     }
 
+    fn show_dynamic(&self) {
+        println!("=== dynamic section ===");
+        for entry in self.dynamic.iter() {
+            entry.show();
+        }
+    }
+
+    fn show_dynamic_symbols(&self) {
+        println!("=== imported symbols ===");
+        for sym in self.dynamic_symbols.iter().filter(|s| s.is_import()) {
+            sym.show();
+        }
+
+        println!("\n=== exported symbols ===");
+        for sym in self.dynamic_symbols.iter().filter(|s| s.is_export()) {
+            sym.show();
+        }
+    }
+
+    fn show_relocations(&self) {
+        if self.relocations.is_empty() {
+            println!("There are no relocations in this file.");
+        }
+        for table in self.relocations.iter() {
+            table.show();
+        }
+    }
+
+    fn show_notes(&self) {
+        if self.notes.is_empty() {
+            println!("There are no notes in this file.");
+        }
+        for note_section in self.notes.iter() {
+            note_section.show();
+        }
+    }
+
+    fn show_security(&self) {
+        self.security_report().show();
+    }
+
+    fn build_id(&self) -> Option<String> {
+        self.notes
+            .iter()
+            .flat_map(|s| s.notes.iter())
+            .find_map(Note::build_id)
+    }
+
     fn show_all_header(&self) {
         self.elf_header.show();
 
@@ -233,18 +620,31 @@ impl Loader for ElfLoader {
         }
     }
 
-    fn analysis(&self) {
+    fn analysis(&self, options: &FormatOptions) {
+        let symbols = self.symbol_map();
         let mut inst_list_overall = HashMap::new();
         for func in self.functions.iter() {
             let mut inst_list = HashMap::new();
-            let call_addrs = func.inst_analysis(&mut inst_list, &self.mem_data);
+            let section = self.section_index(func.offset);
+            let mapping_symbols = section
+                .and_then(|id| self.mapping_symbols.get(&id))
+                .map_or(&[][..], Vec::as_slice);
+            let symbols = section.map_or(symbols.clone(), |id| symbols.section(id));
+            let call_addrs = func.inst_analysis(
+                &mut inst_list,
+                &self.mem_data,
+                self.elf_header.isa(),
+                mapping_symbols,
+                options,
+                &symbols,
+            );
 
             for (name, count) in inst_list.clone() {
                 *inst_list_overall.entry(name).or_insert(0) += count;
             }
 
             let mut inst_list = inst_list.iter().collect::<Vec<(&String, &i32)>>();
-            inst_list.sort_by(|a, b| (-(a.1)).cmp(&(-(b.1))));
+            inst_list.sort_by_key(|t| -t.1);
             for t in inst_list.iter() {
                 println!("{}: {}", t.0, t.1);
             }
@@ -252,18 +652,10 @@ impl Loader for ElfLoader {
             if !call_addrs.is_empty() {
                 print!("calling functions: ");
                 for call_addr in call_addrs {
-                    let call_func = self
-                        .functions
-                        .iter()
-                        .find_map(|f| {
-                            if f.addr == call_addr {
-                                Some(f.name.clone())
-                            } else {
-                                None
-                            }
-                        })
-                        .unwrap_or(format!("{call_addr}"));
-                    print!("func_{call_func} ");
+                    let call_func = symbols
+                        .label(call_addr)
+                        .map_or(format!("func_{call_addr}"), str::to_string);
+                    print!("{call_func} ");
                 }
                 println!();
             }
@@ -273,7 +665,7 @@ impl Loader for ElfLoader {
         println!("======================");
         let mut inst_count = 0;
         let mut inst_list_overall = inst_list_overall.iter().collect::<Vec<(&String, &i32)>>();
-        inst_list_overall.sort_by(|a, b| (-(a.1)).cmp(&(-(b.1))));
+        inst_list_overall.sort_by_key(|t| -t.1);
         for t in inst_list_overall.iter() {
             inst_count += t.1;
             println!("{}: {}", t.0, t.1);
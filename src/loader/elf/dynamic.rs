@@ -0,0 +1,203 @@
+use crate::loader::{Arch, Endian};
+
+pub const DT_NULL: u64 = 0;
+pub const DT_NEEDED: u64 = 1;
+pub const DT_PLTRELSZ: u64 = 2;
+pub const DT_PLTGOT: u64 = 3;
+pub const DT_HASH: u64 = 4;
+pub const DT_STRTAB: u64 = 5;
+pub const DT_SYMTAB: u64 = 6;
+pub const DT_RELA: u64 = 7;
+pub const DT_RELASZ: u64 = 8;
+pub const DT_RELAENT: u64 = 9;
+pub const DT_STRSZ: u64 = 10;
+pub const DT_SYMENT: u64 = 11;
+pub const DT_INIT: u64 = 12;
+pub const DT_FINI: u64 = 13;
+pub const DT_SONAME: u64 = 14;
+pub const DT_RPATH: u64 = 15;
+pub const DT_SYMBOLIC: u64 = 16;
+pub const DT_REL: u64 = 17;
+pub const DT_RELSZ: u64 = 18;
+pub const DT_RELENT: u64 = 19;
+pub const DT_PLTREL: u64 = 20;
+pub const DT_DEBUG: u64 = 21;
+pub const DT_TEXTREL: u64 = 22;
+pub const DT_JMPREL: u64 = 23;
+pub const DT_BIND_NOW: u64 = 24;
+pub const DT_INIT_ARRAY: u64 = 25;
+pub const DT_FINI_ARRAY: u64 = 26;
+pub const DT_INIT_ARRAYSZ: u64 = 27;
+pub const DT_FINI_ARRAYSZ: u64 = 28;
+pub const DT_RUNPATH: u64 = 29;
+pub const DT_FLAGS: u64 = 30;
+pub const DT_PREINIT_ARRAY: u64 = 32;
+pub const DT_PREINIT_ARRAYSZ: u64 = 33;
+pub const DT_SYMTAB_SHNDX: u64 = 34;
+pub const DT_RELRSZ: u64 = 35;
+pub const DT_RELR: u64 = 36;
+pub const DT_RELRENT: u64 = 37;
+pub const DT_GNU_HASH: u64 = 0x6fff_fef5;
+pub const DT_VERSYM: u64 = 0x6fff_fff0;
+pub const DT_RELACOUNT: u64 = 0x6fff_fff9;
+pub const DT_RELCOUNT: u64 = 0x6fff_fffa;
+pub const DT_FLAGS_1: u64 = 0x6fff_fffb;
+pub const DT_VERDEF: u64 = 0x6fff_fffc;
+pub const DT_VERDEFNUM: u64 = 0x6fff_fffd;
+pub const DT_VERNEED: u64 = 0x6fff_fffe;
+pub const DT_VERNEEDNUM: u64 = 0x6fff_ffff;
+
+const DF_NAMES: [&str; 5] = ["ORIGIN", "SYMBOLIC", "TEXTREL", "BIND_NOW", "STATIC_TLS"];
+
+const DF_1_NAMES: [&str; 28] = [
+    "NOW",
+    "GLOBAL",
+    "GROUP",
+    "NODELETE",
+    "LOADFLTR",
+    "INITFIRST",
+    "NOOPEN",
+    "ORIGIN",
+    "DIRECT",
+    "TRANS",
+    "INTERPOSE",
+    "NODEFLIB",
+    "NODUMP",
+    "CONFALT",
+    "ENDFILTEE",
+    "DISPRELDNE",
+    "DISPRELPND",
+    "NODIRECT",
+    "IGNMULDEF",
+    "NOKSYMS",
+    "NOHDR",
+    "EDITED",
+    "NORELOC",
+    "SYMINTPOSE",
+    "GLOBAUDIT",
+    "SINGLETON",
+    "STUB",
+    "PIE",
+];
+
+pub struct DynamicEntry {
+    pub d_tag: u64,
+    pub d_val: u64,
+    /// string for DT_NEEDED, DT_SONAME, DT_RPATH and DT_RUNPATH
+    pub name: Option<String>,
+}
+
+impl DynamicEntry {
+    pub fn new(mmap: &[u8], arch: Arch, endian: Endian, offset: usize) -> DynamicEntry {
+        let (d_tag, d_val) = match arch {
+            Arch::Bit32 => (
+                endian.get_u32(mmap, offset) as u64,
+                endian.get_u32(mmap, offset + 4) as u64,
+            ),
+            Arch::Bit64 => (
+                endian.get_u64(mmap, offset),
+                endian.get_u64(mmap, offset + 8),
+            ),
+        };
+
+        DynamicEntry {
+            d_tag,
+            d_val,
+            name: None,
+        }
+    }
+
+    pub fn has_string(&self) -> bool {
+        matches!(self.d_tag, DT_NEEDED | DT_SONAME | DT_RPATH | DT_RUNPATH)
+    }
+
+    pub fn tag_to_str(&self) -> &'static str {
+        match self.d_tag {
+            DT_NULL => "DT_NULL",
+            DT_NEEDED => "DT_NEEDED",
+            DT_PLTRELSZ => "DT_PLTRELSZ",
+            DT_PLTGOT => "DT_PLTGOT",
+            DT_HASH => "DT_HASH",
+            DT_STRTAB => "DT_STRTAB",
+            DT_SYMTAB => "DT_SYMTAB",
+            DT_RELA => "DT_RELA",
+            DT_RELASZ => "DT_RELASZ",
+            DT_RELAENT => "DT_RELAENT",
+            DT_STRSZ => "DT_STRSZ",
+            DT_SYMENT => "DT_SYMENT",
+            DT_INIT => "DT_INIT",
+            DT_FINI => "DT_FINI",
+            DT_SONAME => "DT_SONAME",
+            DT_RPATH => "DT_RPATH",
+            DT_SYMBOLIC => "DT_SYMBOLIC",
+            DT_REL => "DT_REL",
+            DT_RELSZ => "DT_RELSZ",
+            DT_RELENT => "DT_RELENT",
+            DT_PLTREL => "DT_PLTREL",
+            DT_DEBUG => "DT_DEBUG",
+            DT_TEXTREL => "DT_TEXTREL",
+            DT_JMPREL => "DT_JMPREL",
+            DT_BIND_NOW => "DT_BIND_NOW",
+            DT_INIT_ARRAY => "DT_INIT_ARRAY",
+            DT_FINI_ARRAY => "DT_FINI_ARRAY",
+            DT_INIT_ARRAYSZ => "DT_INIT_ARRAYSZ",
+            DT_FINI_ARRAYSZ => "DT_FINI_ARRAYSZ",
+            DT_RUNPATH => "DT_RUNPATH",
+            DT_FLAGS => "DT_FLAGS",
+            DT_PREINIT_ARRAY => "DT_PREINIT_ARRAY",
+            DT_PREINIT_ARRAYSZ => "DT_PREINIT_ARRAYSZ",
+            DT_SYMTAB_SHNDX => "DT_SYMTAB_SHNDX",
+            DT_RELRSZ => "DT_RELRSZ",
+            DT_RELR => "DT_RELR",
+            DT_RELRENT => "DT_RELRENT",
+            DT_GNU_HASH => "DT_GNU_HASH",
+            DT_VERSYM => "DT_VERSYM",
+            DT_RELACOUNT => "DT_RELACOUNT",
+            DT_RELCOUNT => "DT_RELCOUNT",
+            DT_FLAGS_1 => "DT_FLAGS_1",
+            DT_VERDEF => "DT_VERDEF",
+            DT_VERDEFNUM => "DT_VERDEFNUM",
+            DT_VERNEED => "DT_VERNEED",
+            DT_VERNEEDNUM => "DT_VERNEEDNUM",
+            _ => "unknown tag",
+        }
+    }
+
+    fn value_to_string(&self) -> String {
+        let flag_names = |names: &[&str]| {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| self.d_val >> bit & 1 == 1)
+                .map(|(_, name)| *name)
+                .collect::<Vec<&str>>()
+                .join(" ")
+        };
+
+        if let Some(name) = &self.name {
+            return format!("[{name}]");
+        }
+
+        match self.d_tag {
+            DT_FLAGS => flag_names(&DF_NAMES),
+            DT_FLAGS_1 => flag_names(&DF_1_NAMES),
+            DT_PLTREL => match self.d_val {
+                DT_REL => "DT_REL".to_string(),
+                DT_RELA => "DT_RELA".to_string(),
+                _ => format!("{:#x}", self.d_val),
+            },
+            DT_PLTRELSZ | DT_RELASZ | DT_RELAENT | DT_STRSZ | DT_SYMENT | DT_RELSZ | DT_RELENT
+            | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT => {
+                format!("{} (bytes)", self.d_val)
+            }
+            DT_RELACOUNT | DT_RELCOUNT | DT_VERDEFNUM | DT_VERNEEDNUM => {
+                format!("{}", self.d_val)
+            }
+            _ => format!("{:#x}", self.d_val),
+        }
+    }
+
+    pub fn show(&self) {
+        println!("{:<20}{}", self.tag_to_str(), self.value_to_string());
+    }
+}
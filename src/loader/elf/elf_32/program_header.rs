@@ -1,6 +1,6 @@
 use super::ElfHeader32;
 use crate::loader::elf::ProgramHeader;
-use crate::loader::get_u32;
+use crate::loader::Endian;
 
 fn get_segment_type_name(segment_type: u32) -> &'static str {
     match segment_type {
@@ -26,25 +26,29 @@ pub struct ProgramHeader32 {
     p_memsz: u32,
     p_flags: u32,
     p_align: u32,
+    endian: Endian,
 }
 
 impl ProgramHeader32 {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(mmap: &[u8], elf_header: &ElfHeader32) -> Vec<Box<dyn ProgramHeader>> {
         let mut new_prog: Vec<Box<dyn ProgramHeader>> = Vec::new();
+        let endian = elf_header.endian();
 
         for segment_num in 0..elf_header.e_phnum {
             let segment_start: usize =
                 (elf_header.e_phoff + (elf_header.e_phentsize * segment_num) as u32) as usize;
 
             new_prog.push(Box::new(ProgramHeader32 {
-                p_type: get_u32(mmap, segment_start),
-                p_offset: get_u32(mmap, segment_start + 4),
-                p_vaddr: get_u32(mmap, segment_start + 8),
-                p_paddr: get_u32(mmap, segment_start + 12),
-                p_filesz: get_u32(mmap, segment_start + 16),
-                p_memsz: get_u32(mmap, segment_start + 20),
-                p_flags: get_u32(mmap, segment_start + 24),
-                p_align: get_u32(mmap, segment_start + 28),
+                p_type: endian.get_u32(mmap, segment_start),
+                p_offset: endian.get_u32(mmap, segment_start + 4),
+                p_vaddr: endian.get_u32(mmap, segment_start + 8),
+                p_paddr: endian.get_u32(mmap, segment_start + 12),
+                p_filesz: endian.get_u32(mmap, segment_start + 16),
+                p_memsz: endian.get_u32(mmap, segment_start + 20),
+                p_flags: endian.get_u32(mmap, segment_start + 24),
+                p_align: endian.get_u32(mmap, segment_start + 28),
+                endian,
             }));
         }
 
@@ -53,6 +57,30 @@ impl ProgramHeader32 {
 }
 
 impl ProgramHeader for ProgramHeader32 {
+    fn p_type(&self) -> u32 {
+        self.p_type
+    }
+
+    fn p_flags(&self) -> u32 {
+        self.p_flags
+    }
+
+    fn p_offset(&self) -> u64 {
+        self.p_offset as u64
+    }
+
+    fn p_vaddr(&self) -> u64 {
+        self.p_vaddr as u64
+    }
+
+    fn p_filesz(&self) -> u64 {
+        self.p_filesz as u64
+    }
+
+    fn p_align(&self) -> u64 {
+        self.p_align as u64
+    }
+
     fn show(&self, id: usize) {
         println!("============== program header {}==============", id + 1);
         println!("p_type:\t\t{}", get_segment_type_name(self.p_type));
@@ -73,11 +101,7 @@ impl ProgramHeader for ProgramHeader32 {
             if block % 8 == 0 {
                 println!()
             }
-            print!("{:08x} ", get_u32(mmap, dump_part as usize));
+            print!("{:08x} ", self.endian.get_u32(mmap, dump_part as usize));
         }
     }
-
-    fn offset_and_addr(&self) -> (u64, u64) {
-        (self.p_offset as u64, self.p_paddr as u64)
-    }
 }
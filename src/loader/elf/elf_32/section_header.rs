@@ -1,8 +1,9 @@
 use super::ElfHeader32;
+use crate::disassembler::arm::MappingSymbol;
+use crate::disassembler::symbol::SymbolMap;
+use crate::disassembler::{self, FormatOptions, Isa};
 use crate::loader::elf::SectionHeader;
-use crate::loader::get_u32;
-use iced_x86::{Decoder, DecoderOptions, Formatter, Instruction, NasmFormatter};
-use std::collections::HashMap;
+use crate::loader::Endian;
 
 pub struct SectionHeader32 {
     pub sh_name: String,
@@ -18,27 +19,29 @@ pub struct SectionHeader32 {
 }
 
 impl SectionHeader32 {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(mmap: &[u8], elf_header: &ElfHeader32) -> Vec<Box<dyn SectionHeader>> {
         let mut new_sect: Vec<Box<dyn SectionHeader>> = Vec::new();
+        let endian = elf_header.endian();
         let name_table =
             elf_header.e_shoff + (elf_header.e_shentsize * elf_header.e_shstrndx) as u32;
-        let name_table_off: usize = get_u32(mmap, (name_table as usize) + 16) as usize;
+        let name_table_off: usize = endian.get_u32(mmap, (name_table as usize) + 16) as usize;
 
         for section_num in 0..elf_header.e_shnum {
             let section_head: usize =
                 (elf_header.e_shoff + (elf_header.e_shentsize * section_num) as u32) as usize;
 
             new_sect.push(Box::new(SectionHeader32 {
-                sh_name: SectionHeader32::get_sh_name(mmap, section_head, name_table_off),
-                sh_type: get_u32(mmap, section_head + 4),
-                sh_flags: get_u32(mmap, section_head + 8),
-                sh_addr: get_u32(mmap, section_head + 12),
-                sh_offset: get_u32(mmap, section_head + 16),
-                sh_size: get_u32(mmap, section_head + 20),
-                sh_link: get_u32(mmap, section_head + 24),
-                sh_info: get_u32(mmap, section_head + 28),
-                sh_addralign: get_u32(mmap, section_head + 32),
-                sh_entsize: get_u32(mmap, section_head + 34),
+                sh_name: SectionHeader32::get_sh_name(mmap, endian, section_head, name_table_off),
+                sh_type: endian.get_u32(mmap, section_head + 4),
+                sh_flags: endian.get_u32(mmap, section_head + 8),
+                sh_addr: endian.get_u32(mmap, section_head + 12),
+                sh_offset: endian.get_u32(mmap, section_head + 16),
+                sh_size: endian.get_u32(mmap, section_head + 20),
+                sh_link: endian.get_u32(mmap, section_head + 24),
+                sh_info: endian.get_u32(mmap, section_head + 28),
+                sh_addralign: endian.get_u32(mmap, section_head + 32),
+                sh_entsize: endian.get_u32(mmap, section_head + 36),
             }));
         }
 
@@ -47,8 +50,13 @@ impl SectionHeader32 {
 }
 
 impl SectionHeader for SectionHeader32 {
-    fn get_sh_name(mmap: &[u8], section_head: usize, name_table_head: usize) -> String {
-        let name_id: usize = get_u32(mmap, section_head) as usize;
+    fn get_sh_name(
+        mmap: &[u8],
+        endian: Endian,
+        section_head: usize,
+        name_table_head: usize,
+    ) -> String {
+        let name_id: usize = endian.get_u32(mmap, section_head) as usize;
         let mut sh_name: String = String::new();
 
         for c in mmap[name_table_head + name_id..].iter() {
@@ -65,12 +73,36 @@ impl SectionHeader for SectionHeader32 {
         &self.sh_name
     }
 
+    fn sh_type(&self) -> u32 {
+        self.sh_type
+    }
+
+    fn sh_link(&self) -> u32 {
+        self.sh_link
+    }
+
+    fn sh_addr(&self) -> u64 {
+        self.sh_addr as u64
+    }
+
     fn sh_offset(&self) -> u64 {
         self.sh_offset as u64
     }
 
+    fn sh_size(&self) -> u64 {
+        self.sh_size as u64
+    }
+
+    fn sh_addralign(&self) -> u64 {
+        self.sh_addralign as u64
+    }
+
+    fn sh_entsize(&self) -> u64 {
+        self.sh_entsize as u64
+    }
+
     fn section_range(&self) -> std::ops::Range<u64> {
-        self.sh_offset as u64..(self.sh_offset + self.sh_size) as u64
+        self.sh_offset as u64..self.sh_offset as u64 + self.sh_size as u64
     }
 
     fn type_to_str(&self) -> &'static str {
@@ -109,71 +141,24 @@ impl SectionHeader for SectionHeader32 {
         println!("sh_entsize:\t{}", self.sh_entsize);
     }
 
-    fn dump(&self, mmap: &[u8]) {
-        const HEXBYTES_COLUMN_BYTE_LENGTH: usize = 10;
-        const EXAMPLE_CODE_BITNESS: u32 = 64;
-        const EXAMPLE_CODE_RIP: u64 = 0x0000_0000_8000_0000;
+    fn dump(
+        &self,
+        mmap: &[u8],
+        isa: Isa,
+        mapping_symbols: &[MappingSymbol],
+        options: &FormatOptions,
+        symbols: &SymbolMap,
+    ) {
+        let code_rip: u64 = self.sh_addr as u64;
         if self.sh_flags >> 2 & 1 == 1 {
             let bytes = &mmap[self.sh_offset as usize..(self.sh_offset + self.sh_size) as usize];
-            let mut decoder = Decoder::with_ip(
-                EXAMPLE_CODE_BITNESS,
-                bytes,
-                EXAMPLE_CODE_RIP,
-                DecoderOptions::NONE,
-            );
-            let mut formatter = NasmFormatter::new();
-
-            formatter.options_mut().set_digit_separator("`");
-            formatter.options_mut().set_first_operand_char_index(10);
-
-            let mut output = String::new();
-
-            let mut instruction = Instruction::default();
+            let mut decoder = isa.disassembler(bytes, code_rip, mapping_symbols, options, symbols);
             while decoder.can_decode() {
-                decoder.decode_out(&mut instruction);
-
-                output.clear();
-                formatter.format(&instruction, &mut output);
-
-                print!("{:016X} ", instruction.ip());
-                let start_index = (instruction.ip() - EXAMPLE_CODE_RIP) as usize;
-                let instr_bytes = &bytes[start_index..start_index + instruction.len()];
-                for b in instr_bytes.iter() {
-                    print!("{b:02X}");
-                }
-                if instr_bytes.len() < HEXBYTES_COLUMN_BYTE_LENGTH {
-                    for _ in 0..HEXBYTES_COLUMN_BYTE_LENGTH - instr_bytes.len() {
-                        print!("  ");
-                    }
+                let instruction = decoder.decode();
+                if let Some(name) = symbols.label(instruction.ip) {
+                    disassembler::print_label(instruction.ip, name);
                 }
-                println!(" {output}");
-            }
-        }
-    }
-
-    fn inst_analysis(&self, inst_list: &mut HashMap<String, u32>, mmap: &[u8]) {
-        const EXAMPLE_CODE_BITNESS: u32 = 64;
-        let start_addr: u64 = self.sh_addr as u64;
-        if self.sh_flags >> 2 & 1 == 1 {
-            let bytes = &mmap[self.sh_offset as usize..(self.sh_offset + self.sh_size) as usize];
-            let mut decoder = Decoder::with_ip(
-                EXAMPLE_CODE_BITNESS,
-                bytes,
-                start_addr,
-                DecoderOptions::NONE,
-            );
-            let mut formatter = NasmFormatter::new();
-
-            formatter.options_mut().set_digit_separator("_");
-            formatter.options_mut().set_first_operand_char_index(10);
-
-            let mut instruction = Instruction::default();
-            while decoder.can_decode() {
-                decoder.decode_out(&mut instruction);
-                println!("count: {:?} ", instruction.mnemonic());
-                *inst_list
-                    .entry(format!("{:?}", instruction.mnemonic()))
-                    .or_insert(0) += 1;
+                disassembler::print_line(&instruction);
             }
         }
     }
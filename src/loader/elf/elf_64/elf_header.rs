@@ -1,5 +1,6 @@
+use crate::disassembler::Isa;
 use crate::loader::elf::{ElfHeader, ElfIdentification};
-use crate::loader::{get_u16, get_u32, get_u64};
+use crate::loader::Endian;
 
 fn get_elf_type_name(elf_type: u16) -> &'static str {
     match elf_type {
@@ -32,26 +33,39 @@ pub struct ElfHeader64 {
 impl ElfHeader64 {
     pub fn new(mmap: &[u8], elf_ident: ElfIdentification) -> Box<Self> {
         const ELF_HEADER_START: usize = 16;
+        let endian = elf_ident.get_endian();
         Box::new(ElfHeader64 {
             e_ident: elf_ident,
-            e_type: get_u16(mmap, ELF_HEADER_START),
-            e_machine: get_u16(mmap, ELF_HEADER_START + 2),
-            e_version: get_u32(mmap, ELF_HEADER_START + 4),
-            e_entry: get_u64(mmap, ELF_HEADER_START + 8),
-            e_phoff: get_u64(mmap, ELF_HEADER_START + 16),
-            e_shoff: get_u64(mmap, ELF_HEADER_START + 24),
-            e_flags: get_u32(mmap, ELF_HEADER_START + 32),
-            e_ehsize: get_u16(mmap, ELF_HEADER_START + 36),
-            e_phentsize: get_u16(mmap, ELF_HEADER_START + 38),
-            e_phnum: get_u16(mmap, ELF_HEADER_START + 40),
-            e_shentsize: get_u16(mmap, ELF_HEADER_START + 42),
-            e_shnum: get_u16(mmap, ELF_HEADER_START + 44),
-            e_shstrndx: get_u16(mmap, ELF_HEADER_START + 46),
+            e_type: endian.get_u16(mmap, ELF_HEADER_START),
+            e_machine: endian.get_u16(mmap, ELF_HEADER_START + 2),
+            e_version: endian.get_u32(mmap, ELF_HEADER_START + 4),
+            e_entry: endian.get_u64(mmap, ELF_HEADER_START + 8),
+            e_phoff: endian.get_u64(mmap, ELF_HEADER_START + 16),
+            e_shoff: endian.get_u64(mmap, ELF_HEADER_START + 24),
+            e_flags: endian.get_u32(mmap, ELF_HEADER_START + 32),
+            e_ehsize: endian.get_u16(mmap, ELF_HEADER_START + 36),
+            e_phentsize: endian.get_u16(mmap, ELF_HEADER_START + 38),
+            e_phnum: endian.get_u16(mmap, ELF_HEADER_START + 40),
+            e_shentsize: endian.get_u16(mmap, ELF_HEADER_START + 42),
+            e_shnum: endian.get_u16(mmap, ELF_HEADER_START + 44),
+            e_shstrndx: endian.get_u16(mmap, ELF_HEADER_START + 46),
         })
     }
+
+    pub fn endian(&self) -> Endian {
+        self.e_ident.get_endian()
+    }
 }
 
 impl ElfHeader for ElfHeader64 {
+    fn e_type(&self) -> u16 {
+        self.e_type
+    }
+
+    fn e_machine(&self) -> u16 {
+        self.e_machine
+    }
+
     fn show(&self) {
         println!("================ elf header ================");
         self.e_ident.show();
@@ -69,4 +83,9 @@ impl ElfHeader for ElfHeader64 {
         println!("e_shnum:\t{}", self.e_shnum);
         println!("e_shstrndx:\t{}", self.e_shstrndx);
     }
+
+    fn isa(&self) -> Isa {
+        self.e_ident
+            .get_isa(self.e_machine, self.e_entry, self.e_flags)
+    }
 }
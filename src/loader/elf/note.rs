@@ -0,0 +1,283 @@
+use crate::loader::{Arch, Endian};
+
+const EM_X86_64: u16 = 62;
+const EM_386: u16 = 3;
+const EM_AARCH64: u16 = 183;
+
+const NT_GNU_ABI_TAG: u32 = 1;
+const NT_GNU_HWCAP: u32 = 2;
+const NT_GNU_BUILD_ID: u32 = 3;
+const NT_GNU_GOLD_VERSION: u32 = 4;
+const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+const NT_GO_BUILD_ID: u32 = 4;
+const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe_1a7e;
+
+const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc000_8001;
+const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc000_8002;
+const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc001_0001;
+const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc001_0002;
+
+const ABI_OS_NAMES: [&str; 4] = ["Linux", "Hurd", "Solaris", "FreeBSD"];
+const X86_FEATURE_1_NAMES: [&str; 2] = ["IBT", "SHSTK"];
+const AARCH64_FEATURE_1_NAMES: [&str; 2] = ["BTI", "PAC"];
+const X86_ISA_1_NAMES: [&str; 4] = ["x86-64-baseline", "x86-64-v2", "x86-64-v3", "x86-64-v4"];
+const X86_FEATURE_2_NAMES: [&str; 12] = [
+    "x86", "x87", "MMX", "XMM", "YMM", "ZMM", "FXSR", "XSAVE", "XSAVEOPT", "XSAVEC", "TMM", "MASK",
+];
+
+pub struct Property {
+    pub pr_type: u32,
+    pub pr_data: u64,
+}
+
+pub enum NoteDesc {
+    AbiTag { os: u32, version: [u32; 3] },
+    BuildId(Vec<u8>),
+    Properties(Vec<Property>),
+    GoBuildId(String),
+    PackageMetadata(String),
+    Other(Vec<u8>),
+}
+
+pub struct Note {
+    pub owner: String,
+    pub n_type: u32,
+    pub desc: NoteDesc,
+}
+
+pub struct NoteSection {
+    /// section name, or `PT_NOTE` when the notes come from a segment
+    pub name: String,
+    pub offset: u64,
+    pub e_machine: u16,
+    pub notes: Vec<Note>,
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn flag_names(value: u64, names: &[&str]) -> String {
+    let mut flags = names
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| value >> bit & 1 == 1)
+        .map(|(_, name)| name.to_string())
+        .collect::<Vec<String>>();
+    let unknown = value & !((1 << names.len()) - 1);
+    if unknown != 0 {
+        flags.push(format!("{unknown:#x}"));
+    }
+    if flags.is_empty() {
+        "<None>".to_string()
+    } else {
+        flags.join(", ")
+    }
+}
+
+impl Property {
+    fn read_all(arch: Arch, endian: Endian, desc: &[u8]) -> Vec<Property> {
+        let align = match arch {
+            Arch::Bit32 => 4,
+            Arch::Bit64 => 8,
+        };
+        let mut properties = Vec::new();
+        let mut pos = 0;
+        while pos + 8 <= desc.len() {
+            let pr_type = endian.get_u32(desc, pos);
+            let pr_datasz = endian.get_u32(desc, pos + 4) as usize;
+            let data = pos + 8;
+            if data + pr_datasz > desc.len() {
+                break;
+            }
+            let pr_data = match pr_datasz {
+                4 => endian.get_u32(desc, data) as u64,
+                8 => endian.get_u64(desc, data),
+                _ => 0,
+            };
+            properties.push(Property { pr_type, pr_data });
+            pos = align_up(data + pr_datasz, align);
+        }
+
+        properties
+    }
+
+    /// CET features on x86 and BTI/PAC on AArch64 that every linked object was built with
+    pub fn feature_1(&self, e_machine: u16) -> Option<Vec<&'static str>> {
+        let names: &[&'static str] = match (self.pr_type, e_machine) {
+            (GNU_PROPERTY_X86_FEATURE_1_AND, EM_386 | EM_X86_64) => &X86_FEATURE_1_NAMES,
+            (GNU_PROPERTY_AARCH64_FEATURE_1_AND, EM_AARCH64) => &AARCH64_FEATURE_1_NAMES,
+            _ => return None,
+        };
+        Some(
+            names
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| self.pr_data >> bit & 1 == 1)
+                .map(|(_, name)| *name)
+                .collect(),
+        )
+    }
+
+    pub fn show(&self, e_machine: u16) {
+        let is_x86 = matches!(e_machine, EM_386 | EM_X86_64);
+        let (name, value) = match self.pr_type {
+            GNU_PROPERTY_STACK_SIZE => ("stack size", format!("{:#x}", self.pr_data)),
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => ("no copy on protected", String::new()),
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND if e_machine == EM_AARCH64 => (
+                "AArch64 feature",
+                flag_names(self.pr_data, &AARCH64_FEATURE_1_NAMES),
+            ),
+            GNU_PROPERTY_X86_FEATURE_1_AND if is_x86 => (
+                "x86 feature",
+                flag_names(self.pr_data, &X86_FEATURE_1_NAMES),
+            ),
+            GNU_PROPERTY_X86_ISA_1_NEEDED if is_x86 => {
+                ("x86 ISA needed", flag_names(self.pr_data, &X86_ISA_1_NAMES))
+            }
+            GNU_PROPERTY_X86_ISA_1_USED if is_x86 => {
+                ("x86 ISA used", flag_names(self.pr_data, &X86_ISA_1_NAMES))
+            }
+            GNU_PROPERTY_X86_FEATURE_2_NEEDED if is_x86 => (
+                "x86 feature needed",
+                flag_names(self.pr_data, &X86_FEATURE_2_NAMES),
+            ),
+            GNU_PROPERTY_X86_FEATURE_2_USED if is_x86 => (
+                "x86 feature used",
+                flag_names(self.pr_data, &X86_FEATURE_2_NAMES),
+            ),
+            pr_type => {
+                println!("    {pr_type:#x}: {:#x}", self.pr_data);
+                return;
+            }
+        };
+        println!("    {name}: {value}");
+    }
+}
+
+impl Note {
+    pub fn read_all(
+        mmap: &[u8],
+        arch: Arch,
+        endian: Endian,
+        range: std::ops::Range<u64>,
+        align: u64,
+    ) -> Vec<Note> {
+        // notes are 4-byte aligned unless the section/segment asks for 8
+        let align = if align == 8 { 8 } else { 4 };
+        let end = (range.end as usize).min(mmap.len());
+        let mut notes = Vec::new();
+        let mut pos = range.start as usize;
+        while pos + 12 <= end {
+            let namesz = endian.get_u32(mmap, pos) as usize;
+            let descsz = endian.get_u32(mmap, pos + 4) as usize;
+            let n_type = endian.get_u32(mmap, pos + 8);
+            let name = pos + 12;
+            let desc_off = align_up(name + namesz, align);
+            if name + namesz > end || desc_off + descsz > end {
+                break;
+            }
+
+            let owner = String::from_utf8_lossy(&mmap[name..name + namesz])
+                .trim_end_matches('\0')
+                .to_string();
+            let desc_bytes = &mmap[desc_off..desc_off + descsz];
+            let desc_str = || {
+                String::from_utf8_lossy(desc_bytes)
+                    .trim_end_matches('\0')
+                    .to_string()
+            };
+            let desc = match (owner.as_str(), n_type) {
+                ("GNU", NT_GNU_ABI_TAG) if descsz >= 16 => NoteDesc::AbiTag {
+                    os: endian.get_u32(desc_bytes, 0),
+                    version: [
+                        endian.get_u32(desc_bytes, 4),
+                        endian.get_u32(desc_bytes, 8),
+                        endian.get_u32(desc_bytes, 12),
+                    ],
+                },
+                ("GNU", NT_GNU_BUILD_ID) => NoteDesc::BuildId(desc_bytes.to_vec()),
+                ("GNU", NT_GNU_PROPERTY_TYPE_0) => {
+                    NoteDesc::Properties(Property::read_all(arch, endian, desc_bytes))
+                }
+                ("Go", NT_GO_BUILD_ID) => NoteDesc::GoBuildId(desc_str()),
+                ("FDO", NT_FDO_PACKAGING_METADATA) => NoteDesc::PackageMetadata(desc_str()),
+                _ => NoteDesc::Other(desc_bytes.to_vec()),
+            };
+            notes.push(Note {
+                owner,
+                n_type,
+                desc,
+            });
+            pos = align_up(desc_off + descsz, align);
+        }
+
+        notes
+    }
+
+    pub fn type_to_str(&self) -> String {
+        match (self.owner.as_str(), self.n_type) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG".to_string(),
+            ("GNU", NT_GNU_HWCAP) => "NT_GNU_HWCAP".to_string(),
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID".to_string(),
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION".to_string(),
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0".to_string(),
+            ("Go", NT_GO_BUILD_ID) => "GO_BUILDID".to_string(),
+            ("FDO", NT_FDO_PACKAGING_METADATA) => "FDO_PACKAGING_METADATA".to_string(),
+            (_, n_type) => format!("{n_type:#x}"),
+        }
+    }
+
+    pub fn show(&self, e_machine: u16) {
+        println!("{:<20}{}", self.owner, self.type_to_str());
+        match &self.desc {
+            NoteDesc::AbiTag { os, version } => {
+                let os = ABI_OS_NAMES
+                    .get(*os as usize)
+                    .map_or(format!("{os:#x}"), |name| name.to_string());
+                println!(
+                    "    OS: {os}, ABI: {}.{}.{}",
+                    version[0], version[1], version[2]
+                );
+            }
+            NoteDesc::BuildId(id) => println!("    Build ID: {}", to_hex(id)),
+            NoteDesc::Properties(properties) => {
+                for property in properties.iter() {
+                    property.show(e_machine);
+                }
+            }
+            NoteDesc::GoBuildId(id) => println!("    Go build ID: {id}"),
+            NoteDesc::PackageMetadata(json) => println!("    Packaging Metadata: {json}"),
+            NoteDesc::Other(desc) if desc.is_empty() => {}
+            NoteDesc::Other(desc) => println!("    description data: {}", to_hex(desc)),
+        }
+    }
+
+    pub fn build_id(&self) -> Option<String> {
+        match &self.desc {
+            NoteDesc::BuildId(id) => Some(to_hex(id)),
+            _ => None,
+        }
+    }
+}
+
+impl NoteSection {
+    pub fn show(&self) {
+        println!(
+            "\nDisplaying notes found in '{}' at offset {:#x}:",
+            self.name, self.offset
+        );
+        println!("{:<20}type", "owner");
+        for note in self.notes.iter() {
+            note.show(self.e_machine);
+        }
+    }
+}
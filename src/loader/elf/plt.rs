@@ -0,0 +1,161 @@
+use crate::disassembler::symbol::SymbolMap;
+use crate::disassembler::{FlowControl, FormatOptions, Isa};
+use crate::loader::elf::dynamic::{DynamicEntry, DT_PLTGOT};
+use crate::loader::elf::relocation::{self, Relocation, RelocationTable};
+use crate::loader::elf::symbol::Symbol;
+use crate::loader::elf::SectionHeader;
+use crate::loader::function::Function;
+
+const EM_386: u16 = 3;
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const EM_RISCV: u16 = 243;
+
+/// (PLT0 size, entry size) of the lazy binding `.plt` section.
+fn lazy_plt_layout(e_machine: u16) -> Option<(u64, u64)> {
+    match e_machine {
+        EM_386 | EM_X86_64 => Some((16, 16)),
+        EM_ARM => Some((20, 12)),
+        EM_AARCH64 | EM_RISCV => Some((32, 16)),
+        _ => None,
+    }
+}
+
+fn plt_name(reloc: &Relocation, dynamic_symbols: &[Symbol]) -> Option<String> {
+    dynamic_symbols
+        .get(reloc.r_sym as usize)
+        .filter(|_| reloc.r_sym != 0)
+        .map(|sym| format!("{}@plt", sym.name))
+}
+
+/// i386 PIC stubs jump through `disp(%ebx)`, where %ebx holds the GOT address (DT_PLTGOT).
+fn got_relative_target(bytes: &[u8], got: u64) -> Option<u64> {
+    let disp = match *bytes {
+        // jmp *disp32(%ebx)
+        [0xff, 0xa3, b0, b1, b2, b3] => i32::from_le_bytes([b0, b1, b2, b3]),
+        // jmp *disp8(%ebx)
+        [0xff, 0x63, b0] => b0 as i8 as i32,
+        _ => return None,
+    };
+    Some((got as u32).wrapping_add(disp as u32) as u64)
+}
+
+fn stub(sect: &dyn SectionHeader, addr: u64, size: u64, name: String) -> Option<Function> {
+    Some(Function {
+        name,
+        addr,
+        offset: sect
+            .sh_offset()
+            .checked_add(addr.checked_sub(sect.sh_addr())?)?,
+        size,
+    })
+}
+
+/// Names PLT stubs after the imports they jump to, like `puts@plt`.
+///
+/// `.plt` and `.plt.sec` entries follow the order of the JUMP_SLOT relocations,
+/// `.plt.got` entries are matched through the GOT slot their indirect jump reads.
+pub fn create_plt_table(
+    mmap: &[u8],
+    e_machine: u16,
+    isa: Isa,
+    sect_headers: &[Box<dyn SectionHeader>],
+    dynamic: &[DynamicEntry],
+    relocations: &[RelocationTable],
+    dynamic_symbols: &[Symbol],
+) -> Vec<Function> {
+    let is_type = |reloc: &Relocation, suffix: &str| {
+        relocation::type_to_str(e_machine, reloc.r_type).ends_with(suffix)
+    };
+    let jump_slots = relocations
+        .iter()
+        .flat_map(|table| table.relocations.iter())
+        .filter(|reloc| is_type(reloc, "_JUMP_SLOT"))
+        .collect::<Vec<&Relocation>>();
+
+    let mut plt: Vec<Function> = Vec::new();
+    let plt_sec = sect_headers.iter().find(|s| s.sh_name() == ".plt.sec");
+    let lazy_plt = sect_headers.iter().find(|s| s.sh_name() == ".plt");
+    let layout = match (plt_sec, lazy_plt) {
+        (Some(plt_sec), _) => {
+            let entry = match plt_sec.sh_entsize() {
+                0 => plt_sec.sh_size() / jump_slots.len().max(1) as u64,
+                entsize => entsize,
+            };
+            Some((plt_sec, 0, entry))
+        }
+        (None, Some(lazy_plt)) => {
+            lazy_plt_layout(e_machine).map(|(header, entry)| (lazy_plt, header, entry))
+        }
+        (None, None) => None,
+    };
+    if let Some((sect, header, entry)) = layout.filter(|(_, _, entry)| *entry != 0) {
+        for (i, reloc) in jump_slots.iter().enumerate() {
+            let addr = sect.sh_addr() + header + entry * i as u64;
+            if addr + entry > sect.sh_addr() + sect.sh_size() {
+                break;
+            }
+            if let Some(stub) = plt_name(reloc, dynamic_symbols)
+                .and_then(|name| stub(sect.as_ref(), addr, entry, name))
+            {
+                plt.push(stub);
+            }
+        }
+    }
+
+    // skipped when the section lies outside a truncated file
+    let plt_got = sect_headers
+        .iter()
+        .find(|s| s.sh_name() == ".plt.got")
+        .and_then(|s| {
+            let range = s.section_range();
+            Some((s, mmap.get(range.start as usize..range.end as usize)?))
+        });
+    if let Some((plt_got, bytes)) = plt_got {
+        let entry = match plt_got.sh_entsize() {
+            0 => 8,
+            entsize => entsize,
+        };
+        let got = dynamic
+            .iter()
+            .find(|entry| entry.d_tag == DT_PLTGOT)
+            .map(|entry| entry.d_val)
+            .or_else(|| {
+                sect_headers
+                    .iter()
+                    .find(|s| s.sh_name() == ".got.plt")
+                    .map(|s| s.sh_addr())
+            });
+        let mut decoder = isa.disassembler(
+            bytes,
+            plt_got.sh_addr(),
+            &[],
+            &FormatOptions::default(),
+            &SymbolMap::default(),
+        );
+        while decoder.can_decode() {
+            let instruction = decoder.decode();
+            if instruction.flow_control != FlowControl::IndirectBranch {
+                continue;
+            }
+            let target = match (e_machine, got) {
+                (EM_386, Some(got)) if instruction.target.is_none() => {
+                    got_relative_target(&instruction.bytes, got)
+                }
+                _ => instruction.target,
+            };
+            let reloc = relocations
+                .iter()
+                .flat_map(|table| table.relocations.iter())
+                .filter(|reloc| is_type(reloc, "_GLOB_DAT") || is_type(reloc, "_JUMP_SLOT"))
+                .find(|reloc| Some(reloc.r_offset) == target);
+            if let Some(name) = reloc.and_then(|reloc| plt_name(reloc, dynamic_symbols)) {
+                let addr = plt_got.sh_addr() + (instruction.ip - plt_got.sh_addr()) / entry * entry;
+                plt.extend(stub(plt_got.as_ref(), addr, entry, name));
+            }
+        }
+    }
+
+    plt
+}
@@ -0,0 +1,335 @@
+use crate::loader::elf::symbol::Symbol;
+use crate::loader::{Arch, Endian};
+
+const EM_386: u16 = 3;
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const EM_RISCV: u16 = 243;
+
+pub struct Relocation {
+    pub r_offset: u64,
+    pub r_type: u32,
+    pub r_sym: u32,
+    /// `None` for SHT_REL entries, whose addend is stored at the relocated place
+    pub r_addend: Option<i64>,
+    pub symbol: Option<String>,
+}
+
+pub struct RelocationTable {
+    pub name: String,
+    pub offset: u64,
+    pub e_machine: u16,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Relocation {
+    pub fn new(
+        mmap: &[u8],
+        arch: Arch,
+        endian: Endian,
+        offset: usize,
+        is_rela: bool,
+        symbols: &[Symbol],
+    ) -> Relocation {
+        let (r_offset, r_sym, r_type, r_addend) = match arch {
+            Arch::Bit32 => {
+                let r_info = endian.get_u32(mmap, offset + 4);
+                (
+                    endian.get_u32(mmap, offset) as u64,
+                    r_info >> 8,
+                    r_info & 0xff,
+                    is_rela.then(|| endian.get_u32(mmap, offset + 8) as i32 as i64),
+                )
+            }
+            Arch::Bit64 => {
+                let r_info = endian.get_u64(mmap, offset + 8);
+                (
+                    endian.get_u64(mmap, offset),
+                    (r_info >> 32) as u32,
+                    (r_info & 0xffff_ffff) as u32,
+                    is_rela.then(|| endian.get_u64(mmap, offset + 16) as i64),
+                )
+            }
+        };
+        let symbol = match r_sym {
+            0 => None,
+            _ => symbols.get(r_sym as usize).map(|s| s.versioned_name()),
+        };
+
+        Relocation {
+            r_offset,
+            r_type,
+            r_sym,
+            r_addend,
+            symbol,
+        }
+    }
+
+    pub fn show(&self, e_machine: u16) {
+        let target = match (&self.symbol, self.r_addend) {
+            (Some(symbol), Some(addend)) if addend < 0 => format!("{symbol} - {:x}", -addend),
+            (Some(symbol), Some(addend)) => format!("{symbol} + {addend:x}"),
+            (Some(symbol), None) => symbol.clone(),
+            (None, Some(addend)) => format!("{addend:x}"),
+            (None, None) => String::new(),
+        };
+        let r_type = match type_to_str(e_machine, self.r_type) {
+            "unknown type" => format!("{:#x}", self.r_type),
+            name => name.to_string(),
+        };
+        println!("{:016x}  {:<28}{}", self.r_offset, r_type, target);
+    }
+}
+
+impl RelocationTable {
+    pub fn show(&self) {
+        println!(
+            "\nRelocation section '{}' at offset {:#x} contains {} entries:",
+            self.name,
+            self.offset,
+            self.relocations.len()
+        );
+        println!("{:<18}{:<28}symbol + addend", "offset", "type");
+        for reloc in self.relocations.iter() {
+            reloc.show(self.e_machine);
+        }
+    }
+}
+
+pub fn type_to_str(e_machine: u16, r_type: u32) -> &'static str {
+    match e_machine {
+        EM_386 => i386_type_to_str(r_type),
+        EM_ARM => arm_type_to_str(r_type),
+        EM_X86_64 => x86_64_type_to_str(r_type),
+        EM_AARCH64 => aarch64_type_to_str(r_type),
+        EM_RISCV => riscv_type_to_str(r_type),
+        _ => "unknown type",
+    }
+}
+
+fn x86_64_type_to_str(r_type: u32) -> &'static str {
+    match r_type {
+        0 => "R_X86_64_NONE",
+        1 => "R_X86_64_64",
+        2 => "R_X86_64_PC32",
+        3 => "R_X86_64_GOT32",
+        4 => "R_X86_64_PLT32",
+        5 => "R_X86_64_COPY",
+        6 => "R_X86_64_GLOB_DAT",
+        7 => "R_X86_64_JUMP_SLOT",
+        8 => "R_X86_64_RELATIVE",
+        9 => "R_X86_64_GOTPCREL",
+        10 => "R_X86_64_32",
+        11 => "R_X86_64_32S",
+        12 => "R_X86_64_16",
+        13 => "R_X86_64_PC16",
+        14 => "R_X86_64_8",
+        15 => "R_X86_64_PC8",
+        16 => "R_X86_64_DTPMOD64",
+        17 => "R_X86_64_DTPOFF64",
+        18 => "R_X86_64_TPOFF64",
+        19 => "R_X86_64_TLSGD",
+        20 => "R_X86_64_TLSLD",
+        21 => "R_X86_64_DTPOFF32",
+        22 => "R_X86_64_GOTTPOFF",
+        23 => "R_X86_64_TPOFF32",
+        24 => "R_X86_64_PC64",
+        25 => "R_X86_64_GOTOFF64",
+        26 => "R_X86_64_GOTPC32",
+        27 => "R_X86_64_GOT64",
+        28 => "R_X86_64_GOTPCREL64",
+        29 => "R_X86_64_GOTPC64",
+        30 => "R_X86_64_GOTPLT64",
+        31 => "R_X86_64_PLTOFF64",
+        32 => "R_X86_64_SIZE32",
+        33 => "R_X86_64_SIZE64",
+        34 => "R_X86_64_GOTPC32_TLSDESC",
+        35 => "R_X86_64_TLSDESC_CALL",
+        36 => "R_X86_64_TLSDESC",
+        37 => "R_X86_64_IRELATIVE",
+        38 => "R_X86_64_RELATIVE64",
+        41 => "R_X86_64_GOTPCRELX",
+        42 => "R_X86_64_REX_GOTPCRELX",
+        _ => "unknown type",
+    }
+}
+
+fn i386_type_to_str(r_type: u32) -> &'static str {
+    match r_type {
+        0 => "R_386_NONE",
+        1 => "R_386_32",
+        2 => "R_386_PC32",
+        3 => "R_386_GOT32",
+        4 => "R_386_PLT32",
+        5 => "R_386_COPY",
+        6 => "R_386_GLOB_DAT",
+        7 => "R_386_JUMP_SLOT",
+        8 => "R_386_RELATIVE",
+        9 => "R_386_GOTOFF",
+        10 => "R_386_GOTPC",
+        11 => "R_386_32PLT",
+        14 => "R_386_TLS_TPOFF",
+        15 => "R_386_TLS_IE",
+        16 => "R_386_TLS_GOTIE",
+        17 => "R_386_TLS_LE",
+        18 => "R_386_TLS_GD",
+        19 => "R_386_TLS_LDM",
+        20 => "R_386_16",
+        21 => "R_386_PC16",
+        22 => "R_386_8",
+        23 => "R_386_PC8",
+        24 => "R_386_TLS_GD_32",
+        25 => "R_386_TLS_GD_PUSH",
+        26 => "R_386_TLS_GD_CALL",
+        27 => "R_386_TLS_GD_POP",
+        28 => "R_386_TLS_LDM_32",
+        29 => "R_386_TLS_LDM_PUSH",
+        30 => "R_386_TLS_LDM_CALL",
+        31 => "R_386_TLS_LDM_POP",
+        32 => "R_386_TLS_LDO_32",
+        33 => "R_386_TLS_IE_32",
+        34 => "R_386_TLS_LE_32",
+        35 => "R_386_TLS_DTPMOD32",
+        36 => "R_386_TLS_DTPOFF32",
+        37 => "R_386_TLS_TPOFF32",
+        38 => "R_386_SIZE32",
+        39 => "R_386_TLS_GOTDESC",
+        40 => "R_386_TLS_DESC_CALL",
+        41 => "R_386_TLS_DESC",
+        42 => "R_386_IRELATIVE",
+        43 => "R_386_GOT32X",
+        _ => "unknown type",
+    }
+}
+
+fn aarch64_type_to_str(r_type: u32) -> &'static str {
+    match r_type {
+        0 => "R_AARCH64_NONE",
+        257 => "R_AARCH64_ABS64",
+        258 => "R_AARCH64_ABS32",
+        259 => "R_AARCH64_ABS16",
+        260 => "R_AARCH64_PREL64",
+        261 => "R_AARCH64_PREL32",
+        262 => "R_AARCH64_PREL16",
+        263 => "R_AARCH64_MOVW_UABS_G0",
+        264 => "R_AARCH64_MOVW_UABS_G0_NC",
+        265 => "R_AARCH64_MOVW_UABS_G1",
+        266 => "R_AARCH64_MOVW_UABS_G1_NC",
+        267 => "R_AARCH64_MOVW_UABS_G2",
+        268 => "R_AARCH64_MOVW_UABS_G2_NC",
+        269 => "R_AARCH64_MOVW_UABS_G3",
+        270 => "R_AARCH64_MOVW_SABS_G0",
+        271 => "R_AARCH64_MOVW_SABS_G1",
+        272 => "R_AARCH64_MOVW_SABS_G2",
+        273 => "R_AARCH64_LD_PREL_LO19",
+        274 => "R_AARCH64_ADR_PREL_LO21",
+        275 => "R_AARCH64_ADR_PREL_PG_HI21",
+        276 => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+        277 => "R_AARCH64_ADD_ABS_LO12_NC",
+        278 => "R_AARCH64_LDST8_ABS_LO12_NC",
+        279 => "R_AARCH64_TSTBR14",
+        280 => "R_AARCH64_CONDBR19",
+        282 => "R_AARCH64_JUMP26",
+        283 => "R_AARCH64_CALL26",
+        284 => "R_AARCH64_LDST16_ABS_LO12_NC",
+        285 => "R_AARCH64_LDST32_ABS_LO12_NC",
+        286 => "R_AARCH64_LDST64_ABS_LO12_NC",
+        299 => "R_AARCH64_LDST128_ABS_LO12_NC",
+        309 => "R_AARCH64_GOT_LD_PREL19",
+        311 => "R_AARCH64_ADR_GOT_PAGE",
+        312 => "R_AARCH64_LD64_GOT_LO12_NC",
+        1024 => "R_AARCH64_COPY",
+        1025 => "R_AARCH64_GLOB_DAT",
+        1026 => "R_AARCH64_JUMP_SLOT",
+        1027 => "R_AARCH64_RELATIVE",
+        1028 => "R_AARCH64_TLS_DTPMOD",
+        1029 => "R_AARCH64_TLS_DTPREL",
+        1030 => "R_AARCH64_TLS_TPREL",
+        1031 => "R_AARCH64_TLSDESC",
+        1032 => "R_AARCH64_IRELATIVE",
+        _ => "unknown type",
+    }
+}
+
+fn riscv_type_to_str(r_type: u32) -> &'static str {
+    match r_type {
+        0 => "R_RISCV_NONE",
+        1 => "R_RISCV_32",
+        2 => "R_RISCV_64",
+        3 => "R_RISCV_RELATIVE",
+        4 => "R_RISCV_COPY",
+        5 => "R_RISCV_JUMP_SLOT",
+        6 => "R_RISCV_TLS_DTPMOD32",
+        7 => "R_RISCV_TLS_DTPMOD64",
+        8 => "R_RISCV_TLS_DTPREL32",
+        9 => "R_RISCV_TLS_DTPREL64",
+        10 => "R_RISCV_TLS_TPREL32",
+        11 => "R_RISCV_TLS_TPREL64",
+        16 => "R_RISCV_BRANCH",
+        17 => "R_RISCV_JAL",
+        18 => "R_RISCV_CALL",
+        19 => "R_RISCV_CALL_PLT",
+        20 => "R_RISCV_GOT_HI20",
+        21 => "R_RISCV_TLS_GOT_HI20",
+        22 => "R_RISCV_TLS_GD_HI20",
+        23 => "R_RISCV_PCREL_HI20",
+        24 => "R_RISCV_PCREL_LO12_I",
+        25 => "R_RISCV_PCREL_LO12_S",
+        26 => "R_RISCV_HI20",
+        27 => "R_RISCV_LO12_I",
+        28 => "R_RISCV_LO12_S",
+        29 => "R_RISCV_TPREL_HI20",
+        30 => "R_RISCV_TPREL_LO12_I",
+        31 => "R_RISCV_TPREL_LO12_S",
+        32 => "R_RISCV_TPREL_ADD",
+        33 => "R_RISCV_ADD8",
+        34 => "R_RISCV_ADD16",
+        35 => "R_RISCV_ADD32",
+        36 => "R_RISCV_ADD64",
+        37 => "R_RISCV_SUB8",
+        38 => "R_RISCV_SUB16",
+        39 => "R_RISCV_SUB32",
+        40 => "R_RISCV_SUB64",
+        43 => "R_RISCV_ALIGN",
+        44 => "R_RISCV_RVC_BRANCH",
+        45 => "R_RISCV_RVC_JUMP",
+        46 => "R_RISCV_RVC_LUI",
+        51 => "R_RISCV_RELAX",
+        52 => "R_RISCV_SUB6",
+        53 => "R_RISCV_SET6",
+        54 => "R_RISCV_SET8",
+        55 => "R_RISCV_SET16",
+        56 => "R_RISCV_SET32",
+        57 => "R_RISCV_32_PCREL",
+        58 => "R_RISCV_IRELATIVE",
+        _ => "unknown type",
+    }
+}
+
+fn arm_type_to_str(r_type: u32) -> &'static str {
+    match r_type {
+        0 => "R_ARM_NONE",
+        2 => "R_ARM_ABS32",
+        3 => "R_ARM_REL32",
+        10 => "R_ARM_THM_CALL",
+        17 => "R_ARM_TLS_DTPMOD32",
+        18 => "R_ARM_TLS_DTPOFF32",
+        19 => "R_ARM_TLS_TPOFF32",
+        20 => "R_ARM_COPY",
+        21 => "R_ARM_GLOB_DAT",
+        22 => "R_ARM_JUMP_SLOT",
+        23 => "R_ARM_RELATIVE",
+        28 => "R_ARM_CALL",
+        29 => "R_ARM_JUMP24",
+        30 => "R_ARM_THM_JUMP24",
+        40 => "R_ARM_V4BX",
+        43 => "R_ARM_MOVW_ABS_NC",
+        44 => "R_ARM_MOVT_ABS",
+        47 => "R_ARM_THM_MOVW_ABS_NC",
+        48 => "R_ARM_THM_MOVT_ABS",
+        51 => "R_ARM_THM_JUMP19",
+        160 => "R_ARM_IRELATIVE",
+        _ => "unknown type",
+    }
+}
@@ -0,0 +1,203 @@
+use crate::loader::elf::dynamic::{
+    DT_BIND_NOW, DT_DEBUG, DT_FLAGS, DT_FLAGS_1, DT_RPATH, DT_RUNPATH, DT_TEXTREL,
+};
+use crate::loader::elf::note::NoteDesc;
+use crate::loader::elf::ElfLoader;
+
+const ET_REL: u16 = 1;
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+
+const PT_LOAD: u32 = 1;
+const PT_INTERP: u32 = 3;
+const PT_GNU_STACK: u32 = 0x6474_e551;
+const PT_GNU_RELRO: u32 = 0x6474_e552;
+
+const PF_X: u32 = 1;
+const PF_W: u32 = 2;
+const PF_R: u32 = 4;
+
+const DF_TEXTREL: u64 = 0x4;
+const DF_BIND_NOW: u64 = 0x8;
+const DF_1_NOW: u64 = 0x1;
+const DF_1_PIE: u64 = 0x0800_0000;
+
+pub enum Relro {
+    None,
+    Partial,
+    Full,
+}
+
+pub enum ExeKind {
+    /// position dependent executable
+    Exec,
+    Pie,
+    /// shared object
+    Dso,
+    /// relocatable object, not linked yet
+    Rel,
+}
+
+pub struct SecurityReport {
+    pub relro: Relro,
+    pub nx: bool,
+    pub pie: ExeKind,
+    pub canary: bool,
+    /// fortified `__*_chk` functions the binary calls
+    pub fortified: Vec<String>,
+    /// indexes of segments mapped readable, writable and executable
+    pub rwx_segments: Vec<usize>,
+    pub textrel: bool,
+    pub rpath: Option<String>,
+    pub runpath: Option<String>,
+    /// IBT/SHSTK on x86, BTI/PAC on AArch64
+    pub cet: Vec<&'static str>,
+}
+
+impl SecurityReport {
+    pub fn new(loader: &ElfLoader) -> SecurityReport {
+        let segment = |p_type: u32| loader.prog_headers.iter().find(|p| p.p_type() == p_type);
+        let dynamic = |d_tag: u64| loader.dynamic.iter().find(|e| e.d_tag == d_tag);
+        let flags = dynamic(DT_FLAGS).map_or(0, |e| e.d_val);
+        let flags_1 = dynamic(DT_FLAGS_1).map_or(0, |e| e.d_val);
+
+        let bind_now =
+            dynamic(DT_BIND_NOW).is_some() || flags & DF_BIND_NOW != 0 || flags_1 & DF_1_NOW != 0;
+        let relro = match (segment(PT_GNU_RELRO).is_some(), bind_now) {
+            (false, _) => Relro::None,
+            (true, false) => Relro::Partial,
+            (true, true) => Relro::Full,
+        };
+
+        // without PT_GNU_STACK the kernel maps an executable stack
+        let nx = segment(PT_GNU_STACK).is_some_and(|p| p.p_flags() & PF_X == 0);
+
+        let pie = match loader.elf_header.e_type() {
+            ET_REL => ExeKind::Rel,
+            ET_EXEC => ExeKind::Exec,
+            ET_DYN
+                if flags_1 & DF_1_PIE != 0
+                    || segment(PT_INTERP).is_some()
+                    || dynamic(DT_DEBUG).is_some() =>
+            {
+                ExeKind::Pie
+            }
+            _ => ExeKind::Dso,
+        };
+
+        // static binaries have no imports, so also look at the functions linked in
+        let mut called: Vec<String> = loader
+            .dynamic_symbols
+            .iter()
+            .filter(|s| s.is_import())
+            .map(|s| s.name.clone())
+            .chain(
+                loader
+                    .functions
+                    .iter()
+                    .map(|f| f.name.split('@').next().unwrap_or_default().to_string()),
+            )
+            .collect();
+        called.sort();
+        called.dedup();
+
+        let canary = called
+            .iter()
+            .any(|name| name == "__stack_chk_fail" || name == "__stack_chk_guard");
+        let fortified = called
+            .into_iter()
+            .filter(|name| {
+                name.starts_with("__") && name.ends_with("_chk") && name != "__stack_chk_fail"
+            })
+            .collect();
+
+        let rwx_segments = loader
+            .prog_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.p_type() == PT_LOAD && p.p_flags() & (PF_R | PF_W | PF_X) == 7)
+            .map(|(id, _)| id)
+            .collect();
+
+        let textrel = dynamic(DT_TEXTREL).is_some() || flags & DF_TEXTREL != 0;
+
+        let cet = loader
+            .notes
+            .iter()
+            .flat_map(|s| s.notes.iter())
+            .filter_map(|note| match &note.desc {
+                NoteDesc::Properties(properties) => Some(properties),
+                _ => None,
+            })
+            .flatten()
+            .find_map(|property| property.feature_1(loader.elf_header.e_machine()))
+            .unwrap_or_default();
+
+        SecurityReport {
+            relro,
+            nx,
+            pie,
+            canary,
+            fortified,
+            rwx_segments,
+            textrel,
+            rpath: dynamic(DT_RPATH).and_then(|e| e.name.clone()),
+            runpath: dynamic(DT_RUNPATH).and_then(|e| e.name.clone()),
+            cet,
+        }
+    }
+
+    pub fn show(&self) {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let relro = match self.relro {
+            Relro::None => "no RELRO",
+            Relro::Partial => "partial RELRO",
+            Relro::Full => "full RELRO",
+        };
+        let pie = match self.pie {
+            ExeKind::Exec => "no PIE",
+            ExeKind::Pie => "PIE enabled",
+            ExeKind::Dso => "DSO",
+            ExeKind::Rel => "REL",
+        };
+        let fortify = match self.fortified.is_empty() {
+            true => "no".to_string(),
+            false => format!("yes ({})", self.fortified.join(", ")),
+        };
+        let rwx = match self.rwx_segments.is_empty() {
+            true => "no".to_string(),
+            false => format!(
+                "yes (segment {})",
+                self.rwx_segments
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+        let cet = match self.cet.is_empty() {
+            true => "none".to_string(),
+            false => self.cet.join(", "),
+        };
+
+        println!("=== security ===");
+        println!("{:<16}{}", "RELRO:", relro);
+        println!("{:<16}{}", "stack canary:", yes_no(self.canary));
+        println!("{:<16}{}", "NX:", yes_no(self.nx));
+        println!("{:<16}{}", "PIE:", pie);
+        println!("{:<16}{}", "FORTIFY:", fortify);
+        println!("{:<16}{}", "RWX segments:", rwx);
+        println!("{:<16}{}", "TEXTREL:", yes_no(self.textrel));
+        println!(
+            "{:<16}{}",
+            "RPATH:",
+            self.rpath.as_deref().unwrap_or("none")
+        );
+        println!(
+            "{:<16}{}",
+            "RUNPATH:",
+            self.runpath.as_deref().unwrap_or("none")
+        );
+        println!("{:<16}{}", "CET/BTI:", cet);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::loader::elf::SectionHeader;
+use crate::loader::{get_cstr, Arch, Endian};
+use std::collections::HashMap;
+
+pub const STT_FUNC: u8 = 2;
+const STB_LOCAL: u8 = 0;
+const SHN_ABS: u16 = 0xfff1;
+const SHT_DYNSYM: u32 = 11;
+const SHT_GNU_VERDEF: u32 = 0x6fff_fffd;
+const SHT_GNU_VERNEED: u32 = 0x6fff_fffe;
+const SHT_GNU_VERSYM: u32 = 0x6fff_ffff;
+
+pub struct Symbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+    pub info: u8,
+    pub shndx: u16,
+    pub version: Option<String>,
+    /// non-default version, printed as `name@VERSION` instead of `name@@VERSION`
+    pub hidden: bool,
+}
+
+impl Symbol {
+    pub fn is_import(&self) -> bool {
+        self.shndx == 0 && !self.name.is_empty()
+    }
+
+    pub fn is_export(&self) -> bool {
+        self.shndx != 0 && self.info >> 4 != STB_LOCAL && !self.is_version_definition()
+    }
+
+    /// The ABS symbol a version definition adds for itself, like `VER_1`.
+    fn is_version_definition(&self) -> bool {
+        self.shndx == SHN_ABS && self.version.as_ref() == Some(&self.name)
+    }
+
+    pub fn versioned_name(&self) -> String {
+        match &self.version {
+            Some(_) if self.is_version_definition() => self.name.clone(),
+            Some(version) if self.shndx == 0 || self.hidden => {
+                format!("{}@{}", self.name, version)
+            }
+            Some(version) => format!("{}@@{}", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+
+    pub fn type_to_str(&self) -> &'static str {
+        match self.info & 0xf {
+            0 => "NOTYPE",
+            1 => "OBJECT",
+            STT_FUNC => "FUNC",
+            3 => "SECTION",
+            4 => "FILE",
+            5 => "COMMON",
+            6 => "TLS",
+            10 => "IFUNC",
+            _ => "unknown",
+        }
+    }
+
+    pub fn bind_to_str(&self) -> &'static str {
+        match self.info >> 4 {
+            STB_LOCAL => "LOCAL",
+            1 => "GLOBAL",
+            2 => "WEAK",
+            10 => "UNIQUE",
+            _ => "unknown",
+        }
+    }
+
+    pub fn show(&self) {
+        println!(
+            "{:016x} {:>6} {:<7} {:<7} {}",
+            self.value,
+            self.size,
+            self.type_to_str(),
+            self.bind_to_str(),
+            self.versioned_name()
+        );
+    }
+}
+
+pub fn read_symbols(
+    mmap: &[u8],
+    arch: Arch,
+    endian: Endian,
+    symtab: &dyn SectionHeader,
+    strtab: &dyn SectionHeader,
+) -> Vec<Symbol> {
+    let st_size: usize = match arch {
+        Arch::Bit32 => 16,
+        Arch::Bit64 => 24,
+    };
+    let mut symbols: Vec<Symbol> = Vec::new();
+    for symtab_off in symtab.section_range().step_by(st_size) {
+        let symtab_off = symtab_off as usize;
+        let (info, shndx, value, size) = match arch {
+            Arch::Bit32 => (
+                mmap[symtab_off + 12],
+                endian.get_u16(mmap, symtab_off + 14),
+                endian.get_u32(mmap, symtab_off + 4) as u64,
+                endian.get_u32(mmap, symtab_off + 8) as u64,
+            ),
+            Arch::Bit64 => (
+                mmap[symtab_off + 4],
+                endian.get_u16(mmap, symtab_off + 6),
+                endian.get_u64(mmap, symtab_off + 8),
+                endian.get_u64(mmap, symtab_off + 16),
+            ),
+        };
+        let st_name_off = endian.get_u32(mmap, symtab_off);
+        let name = get_cstr(mmap, (strtab.sh_offset() + st_name_off as u64) as usize);
+
+        symbols.push(Symbol {
+            name,
+            value,
+            size,
+            info,
+            shndx,
+            version: None,
+            hidden: false,
+        });
+    }
+
+    symbols
+}
+
+/// Reads `.dynsym` and attaches versions from `.gnu.version`, `.gnu.version_r` and `.gnu.version_d`.
+pub fn read_dynamic_symbols(
+    mmap: &[u8],
+    arch: Arch,
+    endian: Endian,
+    sect_headers: &[Box<dyn SectionHeader>],
+) -> Vec<Symbol> {
+    let dynsym = match sect_headers.iter().find(|s| s.sh_type() == SHT_DYNSYM) {
+        Some(dynsym) => dynsym,
+        None => return Vec::new(),
+    };
+    let dynstr = match sect_headers.get(dynsym.sh_link() as usize) {
+        Some(dynstr) => dynstr,
+        None => return Vec::new(),
+    };
+    let mut symbols = read_symbols(mmap, arch, endian, dynsym.as_ref(), dynstr.as_ref());
+
+    let versym = match sect_headers.iter().find(|s| s.sh_type() == SHT_GNU_VERSYM) {
+        Some(versym) => versym,
+        None => return symbols,
+    };
+    let version_names = read_version_names(mmap, endian, sect_headers);
+    for (sym, versym_off) in symbols.iter_mut().zip(versym.section_range().step_by(2)) {
+        let versym = endian.get_u16(mmap, versym_off as usize);
+        // 0 and 1 are VER_NDX_LOCAL and VER_NDX_GLOBAL
+        if versym & 0x7fff > 1 {
+            sym.version = version_names.get(&(versym & 0x7fff)).cloned();
+            sym.hidden = versym & 0x8000 != 0;
+        }
+    }
+
+    symbols
+}
+
+fn read_version_names(
+    mmap: &[u8],
+    endian: Endian,
+    sect_headers: &[Box<dyn SectionHeader>],
+) -> HashMap<u16, String> {
+    let mut version_names: HashMap<u16, String> = HashMap::new();
+    let strtab_of = |sect: &dyn SectionHeader| {
+        sect_headers
+            .get(sect.sh_link() as usize)
+            .map(|strtab| strtab.sh_offset() as usize)
+    };
+
+    for verneed in sect_headers
+        .iter()
+        .filter(|s| s.sh_type() == SHT_GNU_VERNEED)
+    {
+        let strtab = match strtab_of(verneed.as_ref()) {
+            Some(strtab) => strtab,
+            None => continue,
+        };
+        let mut vn_off = verneed.sh_offset() as usize;
+        loop {
+            let vn_cnt = endian.get_u16(mmap, vn_off + 2);
+            let mut vna_off = vn_off + endian.get_u32(mmap, vn_off + 8) as usize;
+            for _ in 0..vn_cnt {
+                let vna_other = endian.get_u16(mmap, vna_off + 6);
+                let vna_name = endian.get_u32(mmap, vna_off + 8) as usize;
+                version_names.insert(vna_other, get_cstr(mmap, strtab + vna_name));
+                vna_off += endian.get_u32(mmap, vna_off + 12) as usize;
+            }
+
+            match endian.get_u32(mmap, vn_off + 12) {
+                0 => break,
+                vn_next => vn_off += vn_next as usize,
+            }
+        }
+    }
+
+    for verdef in sect_headers
+        .iter()
+        .filter(|s| s.sh_type() == SHT_GNU_VERDEF)
+    {
+        let strtab = match strtab_of(verdef.as_ref()) {
+            Some(strtab) => strtab,
+            None => continue,
+        };
+        let mut vd_off = verdef.sh_offset() as usize;
+        loop {
+            let vd_ndx = endian.get_u16(mmap, vd_off + 4);
+            let vda_off = vd_off + endian.get_u32(mmap, vd_off + 12) as usize;
+            let vda_name = endian.get_u32(mmap, vda_off) as usize;
+            version_names.insert(vd_ndx, get_cstr(mmap, strtab + vda_name));
+
+            match endian.get_u32(mmap, vd_off + 16) {
+                0 => break,
+                vd_next => vd_off += vd_next as usize,
+            }
+        }
+    }
+
+    version_names
+}
@@ -1,9 +1,95 @@
 #[cfg(test)]
 mod tests {
+    use crate::disassembler::arm::ArmState;
+    use crate::disassembler::{FormatOptions, Instruction, Isa};
+    use crate::loader::elf::dynamic::{DT_FLAGS_1, DT_NEEDED};
+    use crate::loader::elf::note::NoteDesc;
+    use crate::loader::elf::relocation;
+    use crate::loader::elf::security::{ExeKind, Relro};
+    use crate::loader::Endian;
+    use crate::loader::Loader;
     use crate::{loader, visualize};
     use memmap::Mmap;
     use std::fs::File;
 
+    /// Elf32 and Elf64 are built from the same source with gcc defaults.
+    fn assert_notes_and_security(loader: &loader::elf::ElfLoader, build_id: &str) {
+        let notes = || loader.notes.iter().flat_map(|s| s.notes.iter());
+        assert!(notes().any(|note| matches!(
+            note.desc,
+            NoteDesc::AbiTag {
+                os: 0,
+                version: [4, 4, 0]
+            }
+        )));
+        // (pr_type, pr_data): x86 ISA needed baseline, feature used x86, ISA used none
+        let properties = notes()
+            .find_map(|note| match &note.desc {
+                NoteDesc::Properties(properties) => Some(properties),
+                _ => None,
+            })
+            .unwrap()
+            .iter()
+            .map(|p| (p.pr_type, p.pr_data))
+            .collect::<Vec<(u32, u64)>>();
+        assert_eq!(
+            properties,
+            vec![(0xc000_8002, 1), (0xc001_0001, 1), (0xc001_0002, 0)]
+        );
+        // gcc defaults: lazy binding PIE with a non-executable stack, no -fstack-protector
+        let security = loader.security_report();
+        assert!(matches!(security.relro, Relro::Partial));
+        assert!(security.nx);
+        assert!(matches!(security.pie, ExeKind::Pie));
+        assert!(!security.canary);
+        assert!(security.rwx_segments.is_empty());
+        assert!(!security.textrel);
+        assert_eq!(loader.build_id().as_deref(), Some(build_id));
+    }
+
+    /// (type name, symbol) of the relocations in section `name`
+    fn relocations<'a>(
+        loader: &'a loader::elf::ElfLoader,
+        name: &str,
+    ) -> Vec<(&'static str, Option<&'a str>)> {
+        loader
+            .relocations
+            .iter()
+            .filter(|table| table.name == name)
+            .flat_map(|table| table.relocations.iter().map(|r| (table.e_machine, r)))
+            .map(|(e_machine, r)| {
+                (
+                    relocation::type_to_str(e_machine, r.r_type),
+                    r.symbol.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    /// Decodes function `name` the way `-d` lists it, with the labels and mapping symbols
+    /// of its section.
+    fn disassemble_function(loader: &loader::elf::ElfLoader, name: &str) -> Vec<Instruction> {
+        let func = loader.functions.iter().find(|f| f.name == name).unwrap();
+        let section = loader.section_index(func.offset).unwrap();
+        let mapping_symbols = loader
+            .mapping_symbols
+            .get(&section)
+            .map_or(&[][..], Vec::as_slice);
+        let bytes = &loader.mem_data[func.offset as usize..(func.offset + func.size) as usize];
+        let mut decoder = loader.elf_header.isa().disassembler(
+            bytes,
+            func.addr,
+            mapping_symbols,
+            &FormatOptions::default(),
+            &loader.symbol_map().section(section),
+        );
+        let mut instructions = Vec::new();
+        while decoder.can_decode() {
+            instructions.push(decoder.decode());
+        }
+        instructions
+    }
+
     #[test]
     fn elf_32_test() -> std::io::Result<()> {
         let filename = "./test/Elf32";
@@ -16,12 +102,23 @@ mod tests {
         visualize::dump(&mapped_data);
         visualize::diff(&mapped_data, &other);
 
-        let loader = loader::elf::ElfLoader::new(mapped_data);
+        let loader = loader::elf::ElfLoader::load(mapped_data);
         loader.header_show();
         loader.show_segment();
         loader.show_section();
-        loader.disassemble();
-        loader.analysis();
+        loader.show_dynamic();
+        loader.show_dynamic_symbols();
+        loader.show_relocations();
+        loader.show_notes();
+        loader.show_security();
+        assert_notes_and_security(&loader, "d73d15c492b38333d2cd45a5fd4bbe7ea0c77ff7");
+        // i386 code is decoded 32 bit and listed at its virtual address
+        assert!(matches!(loader.elf_header.isa(), Isa::X86(32)));
+        let main = disassemble_function(&loader, "main");
+        assert_eq!(main[0].ip, 0x118d);
+        assert_eq!(main[0].to_string(), "lea       ecx,[esp+4]");
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
 
         Ok(())
     }
@@ -38,12 +135,275 @@ mod tests {
         visualize::dump(&mapped_data);
         visualize::diff(&mapped_data, &other);
 
-        let loader = loader::elf::ElfLoader::new(mapped_data);
+        let loader = loader::elf::ElfLoader::load(mapped_data);
         loader.header_show();
         loader.show_segment();
         loader.show_section();
-        loader.disassemble();
-        loader.analysis();
+        loader.show_dynamic();
+        let needed = loader
+            .dynamic
+            .iter()
+            .filter(|e| e.d_tag == DT_NEEDED)
+            .map(|e| e.name.as_deref())
+            .collect::<Vec<Option<&str>>>();
+        assert_eq!(needed, [Some("libc.so.6")]);
+        // DF_1_PIE
+        let flags_1 = loader.dynamic.iter().find(|e| e.d_tag == DT_FLAGS_1);
+        assert_eq!(flags_1.map(|e| e.d_val), Some(0x0800_0000));
+        loader.show_dynamic_symbols();
+        loader.show_relocations();
+        assert_eq!(
+            relocations(&loader, ".rela.plt"),
+            [("R_X86_64_JUMP_SLOT", Some("puts@GLIBC_2.2.5"))]
+        );
+        loader.show_notes();
+        loader.show_security();
+        assert_notes_and_security(&loader, "1e50e69370dad85d9c18943fdc7c79fa02a0aefe");
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_plt_call_test() -> std::io::Result<()> {
+        let filename = "./test/Elf64";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        // `.plt` stubs follow PLT0 in JUMP_SLOT order
+        assert!(loader
+            .plt
+            .iter()
+            .any(|f| f.name == "puts@plt" && f.addr == 0x1030));
+        let calls = disassemble_function(&loader, "main")
+            .into_iter()
+            .filter(|i| i.is_call())
+            .map(|i| i.operands)
+            .collect::<Vec<String>>();
+        assert_eq!(calls, ["puts@plt"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_32_be_test() -> std::io::Result<()> {
+        let filename = "./test/Mips32be.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        assert_eq!(loader.elf_header.e_machine(), 8);
+        // one section header is read per e_shnum
+        assert_eq!(loader.sect_headers.len(), 9);
+        let mut names = loader
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<&str>>();
+        names.sort_unstable();
+        assert_eq!(names, ["helper", "main"]);
+        loader.header_show();
+        loader.show_segment();
+        loader.show_section();
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_64_be_test() -> std::io::Result<()> {
+        let filename = "./test/Ppc64be.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        assert_eq!(loader.elf_header.e_machine(), 21);
+        // one section header is read per e_shnum
+        assert_eq!(loader.sect_headers.len(), 4);
+        let mut names = loader
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<&str>>();
+        names.sort_unstable();
+        assert_eq!(names, ["helper", "main"]);
+        loader.header_show();
+        loader.show_segment();
+        loader.show_section();
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_riscv_test() -> std::io::Result<()> {
+        let filename = "./test/Riscv64.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        loader.header_show();
+        loader.show_section();
+        let main = disassemble_function(&loader, "main")
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            main[..5],
+            [
+                "addi      sp,sp,-16",
+                "sd        ra,8(sp)",
+                "li        a0,5",
+                "auipc     ra,0x0",
+                "jalr      ra,26(ra) <helper>",
+            ]
+        );
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_aarch64_test() -> std::io::Result<()> {
+        let filename = "./test/Aarch64.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        loader.header_show();
+        loader.show_section();
+        let main = disassemble_function(&loader, "main")
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            main[..5],
+            [
+                "stp       x29, x30, [sp, #-0x10]!",
+                "mov       x29, sp",
+                "mov       w0, #5",
+                "bl        #0x20 <helper>",
+                "cbz       w0, #0x18 <main+0x18>",
+            ]
+        );
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_arm_test() -> std::io::Result<()> {
+        let filename = "./test/Arm32.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        loader.header_show();
+        loader.show_section();
+        loader.show_relocations();
+        assert_eq!(
+            relocations(&loader, ".rel.text"),
+            [("R_ARM_CALL", Some("helper"))]
+        );
+        let main = disassemble_function(&loader, "main")
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            main[..8],
+            [
+                "push      {fp, lr}",
+                "ldr       r0, [pc, #0x10]",
+                "blx       #8 <main+0x8>",
+                "cmp       r0, #0",
+                "bne       #0x18 <main+0x18>",
+                "mov       r0, #1",
+                "pop       {fp, pc}",
+                ".word     0x12345678",
+            ]
+        );
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_arm_be_test() -> std::io::Result<()> {
+        let filename = "./test/Arm32be.o";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        assert!(matches!(
+            loader.elf_header.isa(),
+            Isa::Arm(ArmState::Arm, Endian::Big)
+        ));
+        loader.disassemble(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_plt_got_32_test() -> std::io::Result<()> {
+        let filename = "./test/Plt32Pie";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        let plt = loader
+            .plt
+            .iter()
+            .map(|f| (f.name.as_str(), f.addr))
+            .collect::<Vec<(&str, u64)>>();
+        // `foo@plt` is a `.plt.got` stub jumping through `-4(%ebx)`
+        assert_eq!(plt, [("bar@plt", 0x1010), ("foo@plt", 0x1020)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn elf_stripped_shared_test() -> std::io::Result<()> {
+        let filename = "./test/LibVersioned.so";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::elf::ElfLoader::load(mapped_data);
+        let imports = loader
+            .dynamic_symbols
+            .iter()
+            .filter(|s| s.is_import())
+            .map(|s| s.versioned_name())
+            .collect::<Vec<String>>();
+        let exports = loader
+            .dynamic_symbols
+            .iter()
+            .filter(|s| s.is_export())
+            .map(|s| s.versioned_name())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            imports,
+            [
+                "_ITM_deregisterTMCloneTable",
+                "__gmon_start__",
+                "memcpy@GLIBC_2.14",
+                "_ITM_registerTMCloneTable",
+                "__cxa_finalize@GLIBC_2.2.5",
+            ]
+        );
+        assert_eq!(exports, ["copy@@VER_1", "add@@VER_2", "add@VER_1"]);
+
+        loader.header_show();
+        loader.show_dynamic();
+        loader.show_dynamic_symbols();
+        loader.show_relocations();
+        loader.show_security();
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
 
         Ok(())
     }
@@ -1,78 +1,428 @@
+mod base_reloc;
+mod coff_symbol;
+mod debug_directory;
+mod export;
+mod import;
+mod load_config;
 mod msdos_header;
-mod nt_headers;
+pub mod nt_headers;
+mod resource;
+mod rich_header;
 mod section_header;
+pub mod security;
+mod tls;
 
+use crate::disassembler::symbol::SymbolMap;
+use crate::disassembler::FormatOptions;
 use crate::loader::function::Function;
-use crate::loader::{get_u32, get_u64, Loader};
+use crate::loader::Loader;
+use base_reloc::RelocBlock;
+use coff_symbol::CoffSymbol;
+use debug_directory::DebugEntry;
+use export::ExportDirectory;
+use import::ImportedDll;
+use load_config::LoadConfig;
 use memmap::Mmap;
 use msdos_header::MsDosHeader;
 use nt_headers::NtHeader;
-use section_header::SectionHeader;
+use resource::{Resource, ResourceId, VersionInfo};
+use rich_header::RichHeader;
+use section_header::{rva_to_offset, SectionHeader};
+use security::SecurityReport;
 use std::collections::HashMap;
+use tls::TlsDirectory;
 
 pub struct PeLoader {
     pub msdos_header: MsDosHeader,
+    pub rich_header: Option<RichHeader>,
     pub nt_headers: NtHeader,
     pub sect_headers: Vec<SectionHeader>,
+    pub symbols: Vec<CoffSymbol>,
     pub functions: Vec<Function>,
+    pub load_config: Option<LoadConfig>,
+    pub imports: Vec<ImportedDll>,
+    pub exports: Option<ExportDirectory>,
+    pub base_relocs: Vec<RelocBlock>,
+    pub resources: Vec<Resource>,
+    pub debug_entries: Vec<DebugEntry>,
+    pub tls: Option<TlsDirectory>,
     pub mem_data: Mmap,
 }
 
 impl PeLoader {
-    fn create_func_table(mmap: &[u8], sect_headers: &[SectionHeader]) -> Vec<Function> {
-        let symtab = sect_headers.iter().find(|s| s.name == ".symtab");
-        let strtab = sect_headers.iter().find(|s| s.name == ".strtab");
+    fn create_func_table(
+        symbols: &[CoffSymbol],
+        image_base: u64,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<Function> {
+        let mut func_symbols = symbols
+            .iter()
+            .filter(|sym| sym.is_function() && sym.section_number > 0)
+            .collect::<Vec<&CoffSymbol>>();
+        func_symbols.sort_by_key(|sym| (sym.section_number, sym.value));
+        func_symbols.dedup_by_key(|sym| (sym.section_number, sym.value));
 
-        const ST_SIZE: usize = 32;
         let mut functions: Vec<Function> = Vec::new();
-        if let (Some(symtab), Some(strtab)) = (symtab, strtab) {
-            for symtab_off in (symtab.pointer_to_raw_data
-                ..symtab.pointer_to_raw_data + symtab.size_of_raw_data)
-                .step_by(ST_SIZE)
-            {
-                let st_name_off = get_u32(mmap, symtab_off as usize);
-                let st_name = mmap[(strtab.pointer_to_raw_data + st_name_off) as usize..]
-                    .iter()
-                    .take_while(|c| **c as char != '\0')
-                    .map(|c| *c as char)
-                    .collect::<String>();
-                let st_addr = get_u64(mmap, (symtab_off + 16) as usize);
-                let st_size = get_u64(mmap, (symtab_off + 24) as usize);
-
-                functions.push(Function {
-                    name: st_name,
-                    addr: st_addr,
-                    size: st_size,
-                });
-            }
+        for (i, sym) in func_symbols.iter().enumerate() {
+            let sect = match sect_headers.get(sym.section_number as usize - 1) {
+                Some(sect) if sym.value < sect.size_of_raw_data => sect,
+                _ => continue,
+            };
+            // mingw leaves TotalSize at 0, so fall back to the next function in the section
+            let sect_end = sect.virtual_size.min(sect.size_of_raw_data);
+            let end = match sym.function_size() {
+                0 => Some(
+                    func_symbols
+                        .get(i + 1)
+                        .filter(|next| next.section_number == sym.section_number)
+                        .map_or(sect_end, |next| next.value.min(sect_end)),
+                ),
+                size => sym.value.checked_add(size),
+            };
+            // values come straight from the symbol table, skip those that overflow
+            let (end, rva, offset) = match (
+                end,
+                sect.virtual_address.checked_add(sym.value),
+                sect.pointer_to_raw_data.checked_add(sym.value),
+            ) {
+                (Some(end), Some(rva), Some(offset)) => (end, rva, offset),
+                _ => continue,
+            };
+
+            functions.push(Function {
+                name: sym.name.clone(),
+                addr: image_base + rva as u64,
+                offset: offset as u64,
+                size: end.saturating_sub(sym.value) as u64,
+            });
         }
 
         functions
     }
 
+    fn create_load_config(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Option<LoadConfig> {
+        let dir = nt_headers.data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG)?;
+        rva_to_offset(sect_headers, dir.virtual_address)
+            .map(|offset| LoadConfig::new(mmap, offset, nt_headers.bitness()))
+    }
+
+    fn create_import_table(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<ImportedDll> {
+        let bitness = nt_headers.bitness();
+        let mut imports = match nt_headers.data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_IMPORT)
+        {
+            Some(dir) => {
+                ImportedDll::read_imports(mmap, bitness, sect_headers, dir.virtual_address)
+            }
+            None => Vec::new(),
+        };
+        if let Some(dir) = nt_headers.data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT)
+        {
+            imports.extend(ImportedDll::read_delay_imports(
+                mmap,
+                bitness,
+                nt_headers.image_base(),
+                sect_headers,
+                dir.virtual_address,
+            ));
+        }
+
+        imports
+    }
+
+    fn create_base_relocs(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<RelocBlock> {
+        nt_headers
+            .data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_BASERELOC)
+            .and_then(|dir| {
+                rva_to_offset(sect_headers, dir.virtual_address)
+                    .map(|offset| RelocBlock::read_all(mmap, offset, dir.size as usize))
+            })
+            .unwrap_or_default()
+    }
+
+    fn create_resources(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<Resource> {
+        nt_headers
+            .data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_RESOURCE)
+            .and_then(|dir| rva_to_offset(sect_headers, dir.virtual_address))
+            .map_or(Vec::new(), |root| Resource::read_all(mmap, root))
+    }
+
+    fn create_debug_entries(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<DebugEntry> {
+        nt_headers
+            .data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_DEBUG)
+            .and_then(|dir| {
+                rva_to_offset(sect_headers, dir.virtual_address)
+                    .map(|offset| DebugEntry::read_all(mmap, offset, dir.size as usize))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Function at `rva` in an executable section, sized up to the next of the sorted
+    /// `starts` or the end of the section.
+    fn create_sized_function(
+        name: String,
+        rva: u32,
+        starts: &[u32],
+        image_base: u64,
+        sect_headers: &[SectionHeader],
+    ) -> Option<Function> {
+        const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+        let sect = sect_headers
+            .iter()
+            .find(|s| {
+                (s.virtual_address..s.virtual_address.saturating_add(s.size_of_raw_data))
+                    .contains(&rva)
+            })
+            .filter(|s| s.characteristics & IMAGE_SCN_MEM_EXECUTE != 0)?;
+        let sect_end = sect
+            .virtual_address
+            .saturating_add(sect.virtual_size.min(sect.size_of_raw_data));
+        let end = starts
+            .iter()
+            .find(|start| **start > rva)
+            .map_or(sect_end, |start| (*start).min(sect_end));
+
+        Some(Function {
+            name,
+            addr: image_base + rva as u64,
+            offset: sect.pointer_to_raw_data as u64 + (rva - sect.virtual_address) as u64,
+            size: end.saturating_sub(rva) as u64,
+        })
+    }
+
+    /// Exported code, sized up to the next export or the end of its section.
+    fn create_export_functions(
+        exports: &ExportDirectory,
+        image_base: u64,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<Function> {
+        let mut starts = exports
+            .exports
+            .iter()
+            .filter(|e| e.forwarder.is_none())
+            .map(|e| e.rva)
+            .collect::<Vec<u32>>();
+        starts.sort();
+        starts.dedup();
+
+        exports
+            .exports
+            .iter()
+            .filter(|e| e.forwarder.is_none())
+            .filter_map(|export| {
+                let name = export
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("{}#{}", exports.name, export.ordinal));
+                Self::create_sized_function(name, export.rva, &starts, image_base, sect_headers)
+            })
+            .collect()
+    }
+
+    fn create_tls(
+        mmap: &[u8],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Option<TlsDirectory> {
+        let dir = nt_headers.data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_TLS)?;
+        rva_to_offset(sect_headers, dir.virtual_address).map(|offset| {
+            TlsDirectory::new(
+                mmap,
+                offset,
+                nt_headers.bitness(),
+                nt_headers.image_base(),
+                sect_headers,
+            )
+        })
+    }
+
+    /// Code the loader runs on its own: TLS callbacks first, then the entry point.
+    /// Each is sized up to the next known function.
+    fn create_start_functions(
+        tls: Option<&TlsDirectory>,
+        functions: &[Function],
+        nt_headers: &NtHeader,
+        sect_headers: &[SectionHeader],
+    ) -> Vec<Function> {
+        let image_base = nt_headers.image_base();
+        let mut entries = tls.map_or(Vec::new(), |tls| {
+            tls.callbacks
+                .iter()
+                .enumerate()
+                .map(|(i, va)| {
+                    (
+                        format!("tls_callback_{i}"),
+                        va.wrapping_sub(image_base) as u32,
+                    )
+                })
+                .collect::<Vec<(String, u32)>>()
+        });
+        if nt_headers.address_of_entry_point() != 0 {
+            entries.push((
+                "entry_point".to_string(),
+                nt_headers.address_of_entry_point(),
+            ));
+        }
+
+        let mut starts = functions
+            .iter()
+            .map(|f| f.addr.wrapping_sub(image_base) as u32)
+            .chain(entries.iter().map(|(_, rva)| *rva))
+            .collect::<Vec<u32>>();
+        starts.sort();
+        starts.dedup();
+
+        entries
+            .into_iter()
+            .filter_map(|(name, rva)| {
+                Self::create_sized_function(name, rva, &starts, image_base, sect_headers)
+            })
+            .collect()
+    }
+
+    /// IAT slot address -> name of the imported function the slot is patched with
+    pub fn iat_map(&self) -> HashMap<u64, String> {
+        let image_base = self.nt_headers.image_base();
+        self.imports
+            .iter()
+            .flat_map(|dll| {
+                dll.functions
+                    .iter()
+                    .map(|f| (image_base + f.iat_rva as u64, f.label(&dll.name)))
+            })
+            .collect()
+    }
+
+    pub fn symbol_map(&self) -> SymbolMap {
+        let slot_size = (self.nt_headers.bitness() / 8) as u64;
+        let iat_map = self.iat_map();
+        SymbolMap::new(
+            self.functions
+                .iter()
+                .map(|f| (f.name.as_str(), f.addr, f.size))
+                .chain(
+                    iat_map
+                        .iter()
+                        .map(|(addr, name)| (name.as_str(), *addr, slot_size)),
+                ),
+        )
+    }
+
+    /// Data of every resource of the numbered type `type_id`.
+    fn resource_data(&self, type_id: u32) -> impl Iterator<Item = &[u8]> {
+        self.resources
+            .iter()
+            .filter(move |res| res.type_id == ResourceId::Id(type_id))
+            .filter_map(|res| res.data(&self.mem_data, &self.sect_headers))
+    }
+
+    pub fn version_info(&self) -> Option<VersionInfo> {
+        self.resource_data(resource::RT_VERSION)
+            .find_map(VersionInfo::new)
+    }
+
+    pub fn manifest(&self) -> Option<&[u8]> {
+        self.resource_data(resource::RT_MANIFEST).next()
+    }
+
+    pub fn security_report(&self) -> SecurityReport {
+        SecurityReport::new(self)
+    }
+
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(mapped_data: Mmap) -> Box<dyn Loader> {
+        Box::new(Self::load(mapped_data))
+    }
+
+    pub fn load(mapped_data: Mmap) -> PeLoader {
         let new_msdos = MsDosHeader::new(&mapped_data);
         let new_nt = NtHeader::new(&mapped_data, new_msdos.nt_offset());
-        let new_sect = SectionHeader::new(&mapped_data, new_nt.sect_num(), new_nt.sect_off());
-        let new_func = Self::create_func_table(&mapped_data, &new_sect);
+        let new_rich = RichHeader::new(&mapped_data, new_msdos.nt_offset());
+        let (pointer_to_symtab, number_of_symbols) = new_nt.symbol_table();
+        let string_table =
+            coff_symbol::string_table_offset(&mapped_data, pointer_to_symtab, number_of_symbols);
+        let new_sect = SectionHeader::new(
+            &mapped_data,
+            new_nt.sect_num(),
+            new_nt.sect_off(),
+            string_table,
+        );
+        let new_symbols =
+            CoffSymbol::read_symbols(&mapped_data, pointer_to_symtab, number_of_symbols);
+        let mut new_func = Self::create_func_table(&new_symbols, new_nt.image_base(), &new_sect);
+        let new_load_config = Self::create_load_config(&mapped_data, &new_nt, &new_sect);
+        let new_imports = Self::create_import_table(&mapped_data, &new_nt, &new_sect);
+        let new_exports = new_nt
+            .data_directory(nt_headers::IMAGE_DIRECTORY_ENTRY_EXPORT)
+            .and_then(|dir| {
+                ExportDirectory::new(&mapped_data, &new_sect, dir.virtual_address, dir.size)
+            });
+        let new_base_relocs = Self::create_base_relocs(&mapped_data, &new_nt, &new_sect);
+        let new_resources = Self::create_resources(&mapped_data, &new_nt, &new_sect);
+        let new_debug_entries = Self::create_debug_entries(&mapped_data, &new_nt, &new_sect);
+        if let Some(exports) = &new_exports {
+            // exports already known from the symbol table keep their symbol name
+            let export_func =
+                Self::create_export_functions(exports, new_nt.image_base(), &new_sect)
+                    .into_iter()
+                    .filter(|e| new_func.iter().all(|f| f.addr != e.addr))
+                    .collect::<Vec<Function>>();
+            new_func.extend(export_func);
+        }
+        let new_tls = Self::create_tls(&mapped_data, &new_nt, &new_sect);
+        // like exports, starts already known from the symbol table keep their symbol name
+        let start_func =
+            Self::create_start_functions(new_tls.as_ref(), &new_func, &new_nt, &new_sect)
+                .into_iter()
+                .filter(|s| new_func.iter().all(|f| f.addr != s.addr))
+                .collect::<Vec<Function>>();
+        new_func.extend(start_func);
 
-        Box::new(PeLoader {
+        PeLoader {
             msdos_header: new_msdos,
+            rich_header: new_rich,
             nt_headers: new_nt,
             sect_headers: new_sect,
+            symbols: new_symbols,
             functions: new_func,
+            load_config: new_load_config,
+            imports: new_imports,
+            exports: new_exports,
+            base_relocs: new_base_relocs,
+            resources: new_resources,
+            debug_entries: new_debug_entries,
+            tls: new_tls,
             mem_data: mapped_data,
-        })
+        }
     }
 }
 
 impl Loader for PeLoader {
-    fn mem_data(&self) -> &[u8] {
-        &self.mem_data
-    }
-
     fn header_show(&self) {
         self.msdos_header.show();
+        if let Some(rich) = &self.rich_header {
+            rich.show();
+        }
+        self.nt_headers.show();
     }
 
     fn show_segment(&self) {
@@ -85,25 +435,158 @@ impl Loader for PeLoader {
         }
     }
 
-    fn disassemble(&self) {
+    fn disassemble(&self, options: &FormatOptions) {
+        let symbols = self.symbol_map();
         for sect in &self.sect_headers {
             sect.show();
-            sect.dump(&self.mem_data);
+            sect.dump(
+                &self.mem_data,
+                self.nt_headers.isa(),
+                self.nt_headers.image_base(),
+                options,
+                &symbols,
+            );
+        }
+    }
+
+    fn show_imports(&self) {
+        if self.imports.is_empty() {
+            println!("There are no imports in this file.");
+        }
+        for dll in self.imports.iter() {
+            dll.show(self.nt_headers.image_base());
+        }
+    }
+
+    fn show_exports(&self) {
+        match &self.exports {
+            Some(exports) => exports.show(),
+            None => println!("There are no exports in this file."),
+        }
+    }
+
+    fn show_relocations(&self) {
+        if self.base_relocs.is_empty() {
+            println!("There are no base relocations in this file.");
+            return;
+        }
+        println!("BASE RELOCATIONS #{}", self.base_relocs.len());
+        for block in self.base_relocs.iter() {
+            block.show(&self.mem_data, self.nt_headers.machine(), |rva| {
+                rva_to_offset(&self.sect_headers, rva)
+            });
+        }
+    }
+
+    fn relocate(&self, load_base: u64) -> Option<Vec<u8>> {
+        let delta = load_base.wrapping_sub(self.nt_headers.image_base());
+        let mut data = self.mem_data.to_vec();
+        for block in self.base_relocs.iter() {
+            block.apply(&mut data, delta, self.nt_headers.machine(), |rva| {
+                rva_to_offset(&self.sect_headers, rva)
+            });
+        }
+        Some(data)
+    }
+
+    fn show_debug_directory(&self) {
+        if self.debug_entries.is_empty() {
+            println!("There is no debug directory in this file.");
+            return;
+        }
+        println!("=== debug directory ===");
+        for entry in self.debug_entries.iter() {
+            entry.show();
+        }
+    }
+
+    /// GUID and age of the CodeView record, the key symbol servers index PDBs by
+    fn build_id(&self) -> Option<String> {
+        self.debug_entries.iter().find_map(DebugEntry::pdb_key)
+    }
+
+    fn show_tls(&self) {
+        match &self.tls {
+            Some(tls) => tls.show(|va| {
+                self.functions
+                    .iter()
+                    .find(|f| f.addr == va)
+                    .map(|f| f.name.clone())
+            }),
+            None => println!("There is no TLS directory in this file."),
+        }
+    }
+
+    fn show_resources(&self) {
+        if self.resources.is_empty() {
+            println!("There are no resources in this file.");
+            return;
+        }
+        println!("=== resources ===");
+        println!(
+            "{:<24}{:<14}{:<12}{:<10}code page",
+            "type/name/lang", "type", "rva", "size"
+        );
+        for res in self.resources.iter() {
+            res.show();
+        }
+
+        if let Some(version_info) = self.version_info() {
+            version_info.show();
+        }
+        if let Some(manifest) = self.manifest() {
+            println!("--- manifest ---");
+            println!("{}", String::from_utf8_lossy(manifest));
         }
     }
 
+    fn save_resource(&self, path: &str, output: &str) -> std::io::Result<()> {
+        match self
+            .resources
+            .iter()
+            .find(|res| res.path() == path)
+            .and_then(|res| res.data(&self.mem_data, &self.sect_headers))
+        {
+            Some(data) => std::fs::write(output, data),
+            None => {
+                println!("resource {path} not found");
+                Ok(())
+            }
+        }
+    }
+
+    fn show_symbols(&self) {
+        if self.symbols.is_empty() {
+            println!("There are no COFF symbols in this file.");
+        }
+        for sym in self.symbols.iter() {
+            sym.show();
+        }
+    }
+
+    fn show_security(&self) {
+        self.security_report().show();
+    }
+
     fn show_all_header(&self) {
-        self.msdos_header.show();
-        self.nt_headers.show();
+        self.header_show();
         for sect in &self.sect_headers {
             sect.show();
         }
     }
 
-    fn analysis(&self) {
+    fn analysis(&self, options: &FormatOptions) {
+        let symbols = self.symbol_map();
         for func in self.functions.iter() {
             let mut inst_list = HashMap::new();
-            func.inst_analysis(&mut inst_list, &self.mem_data);
+            func.inst_analysis(
+                &mut inst_list,
+                &self.mem_data,
+                self.nt_headers.isa(),
+                &[],
+                options,
+                &symbols,
+            );
             println!("{inst_list:#?}");
         }
     }
@@ -0,0 +1,250 @@
+use crate::loader::{get_u16, get_u32, get_u64};
+
+const IMAGE_REL_BASED_ABSOLUTE: u8 = 0;
+const IMAGE_REL_BASED_HIGH: u8 = 1;
+const IMAGE_REL_BASED_LOW: u8 = 2;
+const IMAGE_REL_BASED_HIGHLOW: u8 = 3;
+const IMAGE_REL_BASED_HIGHADJ: u8 = 4;
+const IMAGE_REL_BASED_MACHINE_SPECIFIC_5: u8 = 5;
+const IMAGE_REL_BASED_MACHINE_SPECIFIC_7: u8 = 7;
+const IMAGE_REL_BASED_MACHINE_SPECIFIC_8: u8 = 8;
+const IMAGE_REL_BASED_MACHINE_SPECIFIC_9: u8 = 9;
+const IMAGE_REL_BASED_DIR64: u8 = 10;
+
+const IMAGE_FILE_MACHINE_R4000: u16 = 0x166;
+const IMAGE_FILE_MACHINE_MIPS16: u16 = 0x266;
+const IMAGE_FILE_MACHINE_ARM: u16 = 0x1c0;
+const IMAGE_FILE_MACHINE_THUMB: u16 = 0x1c2;
+const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x1c4;
+const IMAGE_FILE_MACHINE_IA64: u16 = 0x200;
+const IMAGE_FILE_MACHINE_LOONGARCH32: u16 = 0x6232;
+const IMAGE_FILE_MACHINE_LOONGARCH64: u16 = 0x6264;
+const IMAGE_FILE_MACHINE_RISCV32: u16 = 0x5032;
+const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+const IMAGE_FILE_MACHINE_RISCV128: u16 = 0x5128;
+
+pub struct BaseRelocation {
+    /// offset into the page, the low 12 bits of the entry
+    pub offset: u16,
+    pub reloc_type: u8,
+    /// low 16 bits of the adjusted value, carried by the slot after a HIGHADJ entry
+    pub param: Option<u16>,
+}
+
+/// One IMAGE_BASE_RELOCATION block covering a 4K page.
+pub struct RelocBlock {
+    pub page_rva: u32,
+    pub size_of_block: u32,
+    pub entries: Vec<BaseRelocation>,
+}
+
+impl BaseRelocation {
+    pub fn type_to_str(&self, machine: u16) -> &'static str {
+        let arm = matches!(
+            machine,
+            IMAGE_FILE_MACHINE_ARM | IMAGE_FILE_MACHINE_THUMB | IMAGE_FILE_MACHINE_ARMNT
+        );
+        let mips = matches!(
+            machine,
+            IMAGE_FILE_MACHINE_R4000 | IMAGE_FILE_MACHINE_MIPS16
+        );
+        let riscv = matches!(
+            machine,
+            IMAGE_FILE_MACHINE_RISCV32 | IMAGE_FILE_MACHINE_RISCV64 | IMAGE_FILE_MACHINE_RISCV128
+        );
+        let loongarch = matches!(
+            machine,
+            IMAGE_FILE_MACHINE_LOONGARCH32 | IMAGE_FILE_MACHINE_LOONGARCH64
+        );
+        match self.reloc_type {
+            IMAGE_REL_BASED_ABSOLUTE => "ABS",
+            IMAGE_REL_BASED_HIGH => "HIGH",
+            IMAGE_REL_BASED_LOW => "LOW",
+            IMAGE_REL_BASED_HIGHLOW => "HIGHLOW",
+            IMAGE_REL_BASED_HIGHADJ => "HIGHADJ",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_5 if arm => "ARM_MOV32",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_5 if mips => "MIPS_JMPADDR",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_5 if riscv => "RISCV_HIGH20",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_7 if arm => "THUMB_MOV32",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_7 if riscv => "RISCV_LOW12I",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_8 if riscv => "RISCV_LOW12S",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_8 if loongarch => "LOONGARCH_MARK_LA",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_9 if mips => "MIPS_JMPADDR16",
+            IMAGE_REL_BASED_MACHINE_SPECIFIC_9 if machine == IMAGE_FILE_MACHINE_IA64 => {
+                "IA64_IMM64"
+            }
+            IMAGE_REL_BASED_DIR64 => "DIR64",
+            _ => "unknown",
+        }
+    }
+}
+
+impl RelocBlock {
+    pub fn read_all(mmap: &[u8], offset: usize, size: usize) -> Vec<RelocBlock> {
+        const HEADER_SIZE: usize = 8;
+        let end = (offset + size).min(mmap.len());
+        let mut blocks = Vec::new();
+        let mut block = offset;
+        while block + HEADER_SIZE <= end {
+            let page_rva = get_u32(mmap, block);
+            let size_of_block = get_u32(mmap, block + 4);
+            if (size_of_block as usize) < HEADER_SIZE {
+                break;
+            }
+
+            let block_end = (block + size_of_block as usize).min(end);
+            let mut entries = Vec::new();
+            let mut entry = block + HEADER_SIZE;
+            while entry + 2 <= block_end {
+                let value = get_u16(mmap, entry);
+                let reloc_type = (value >> 12) as u8;
+                // HIGHADJ takes up two slots
+                let param = match reloc_type {
+                    IMAGE_REL_BASED_HIGHADJ if entry + 4 <= block_end => {
+                        entry += 2;
+                        Some(get_u16(mmap, entry))
+                    }
+                    _ => None,
+                };
+                entries.push(BaseRelocation {
+                    offset: value & 0xfff,
+                    reloc_type,
+                    param,
+                });
+                entry += 2;
+            }
+
+            blocks.push(RelocBlock {
+                page_rva,
+                size_of_block,
+                entries,
+            });
+            block += size_of_block as usize;
+        }
+
+        blocks
+    }
+
+    /// Listed like `dumpbin /relocations`, with the value currently stored at each fixup.
+    pub fn show(&self, mmap: &[u8], machine: u16, to_offset: impl Fn(u32) -> Option<usize>) {
+        println!(
+            "\n{:8X} RVA, {:8X} SizeOfBlock",
+            self.page_rva, self.size_of_block
+        );
+        for entry in self.entries.iter() {
+            let rva = self.page_rva + entry.offset as u32;
+            let value = match (entry.reloc_type, to_offset(rva)) {
+                (IMAGE_REL_BASED_ABSOLUTE, _) | (_, None) => String::new(),
+                (IMAGE_REL_BASED_DIR64, Some(offset)) if offset + 8 <= mmap.len() => {
+                    format!("{:016X}", get_u64(mmap, offset))
+                }
+                (IMAGE_REL_BASED_HIGH | IMAGE_REL_BASED_LOW, Some(offset))
+                    if offset + 2 <= mmap.len() =>
+                {
+                    format!("{:04X}", get_u16(mmap, offset))
+                }
+                (IMAGE_REL_BASED_HIGHADJ, Some(offset)) if offset + 2 <= mmap.len() => format!(
+                    "{:04X} ({:04X})",
+                    get_u16(mmap, offset),
+                    entry.param.unwrap_or(0)
+                ),
+                (_, Some(offset)) if offset + 4 <= mmap.len() => {
+                    format!("{:08X}", get_u32(mmap, offset))
+                }
+                _ => String::new(),
+            };
+            println!(
+                "{:8X}  {:<18} {}",
+                entry.offset,
+                entry.type_to_str(machine),
+                value
+            );
+        }
+    }
+
+    /// Adds `delta` (new base - preferred base) to every fixup in the page.
+    /// `to_offset` maps an rva to an index into `data`, so the same blocks can patch
+    /// both the on-disk file layout and a memory dump in image layout.
+    /// Types that depend on instruction encodings other than ARM/Thumb MOVW/MOVT are skipped.
+    pub fn apply(
+        &self,
+        data: &mut [u8],
+        delta: u64,
+        machine: u16,
+        to_offset: impl Fn(u32) -> Option<usize>,
+    ) {
+        let arm = matches!(
+            machine,
+            IMAGE_FILE_MACHINE_ARM | IMAGE_FILE_MACHINE_THUMB | IMAGE_FILE_MACHINE_ARMNT
+        );
+        for entry in self.entries.iter() {
+            let offset = match to_offset(self.page_rva + entry.offset as u32) {
+                Some(offset) => offset,
+                None => continue,
+            };
+            match entry.reloc_type {
+                IMAGE_REL_BASED_HIGH if offset + 2 <= data.len() => {
+                    let value = get_u16(data, offset).wrapping_add((delta >> 16) as u16);
+                    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+                }
+                IMAGE_REL_BASED_LOW if offset + 2 <= data.len() => {
+                    let value = get_u16(data, offset).wrapping_add(delta as u16);
+                    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+                }
+                IMAGE_REL_BASED_HIGHLOW if offset + 4 <= data.len() => {
+                    let value = get_u32(data, offset).wrapping_add(delta as u32);
+                    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                IMAGE_REL_BASED_HIGHADJ if offset + 2 <= data.len() => {
+                    // the high half is rounded with the sign extended low half in `param`
+                    let full = ((get_u16(data, offset) as u32) << 16)
+                        .wrapping_add(entry.param.unwrap_or(0) as i16 as i32 as u32)
+                        .wrapping_add(delta as u32)
+                        .wrapping_add(0x8000);
+                    let value = (full >> 16) as u16;
+                    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+                }
+                IMAGE_REL_BASED_DIR64 if offset + 8 <= data.len() => {
+                    let value = get_u64(data, offset).wrapping_add(delta);
+                    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+                }
+                IMAGE_REL_BASED_MACHINE_SPECIFIC_5 if arm && offset + 8 <= data.len() => {
+                    Self::apply_mov32(data, offset, delta as u32, false);
+                }
+                IMAGE_REL_BASED_MACHINE_SPECIFIC_7 if arm && offset + 8 <= data.len() => {
+                    Self::apply_mov32(data, offset, delta as u32, true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// MOVW at `offset` followed by MOVT loading a 32 bit address.
+    fn apply_mov32(data: &mut [u8], offset: usize, delta: u32, thumb: bool) {
+        let read = |insn: u32| -> u32 {
+            if thumb {
+                // halfwords hw1:hw2, imm16 = imm4:i:imm3:imm8
+                let (hw1, hw2) = (insn & 0xffff, insn >> 16);
+                (hw1 & 0xf) << 12 | (hw1 >> 10 & 1) << 11 | (hw2 >> 12 & 7) << 8 | hw2 & 0xff
+            } else {
+                // imm16 = imm4:imm12
+                (insn >> 16 & 0xf) << 12 | insn & 0xfff
+            }
+        };
+        let write = |insn: u32, imm: u32| -> u32 {
+            if thumb {
+                let hw1 = insn & 0xfbf0 | imm >> 12 & 0xf | (imm >> 11 & 1) << 10;
+                let hw2 = (insn >> 16) & 0x8f00 | (imm >> 8 & 7) << 12 | imm & 0xff;
+                hw2 << 16 | hw1
+            } else {
+                insn & 0xfff0_f000 | (imm >> 12 & 0xf) << 16 | imm & 0xfff
+            }
+        };
+
+        let movw = get_u32(data, offset);
+        let movt = get_u32(data, offset + 4);
+        let value = (read(movt) << 16 | read(movw)).wrapping_add(delta);
+        data[offset..offset + 4].copy_from_slice(&write(movw, value & 0xffff).to_le_bytes());
+        data[offset + 4..offset + 8].copy_from_slice(&write(movt, value >> 16).to_le_bytes());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::loader::{get_cstr, get_u16, get_u32};
+
+const IMAGE_SYM_CLASS_EXTERNAL: u8 = 2;
+const IMAGE_SYM_CLASS_STATIC: u8 = 3;
+const IMAGE_SYM_CLASS_FUNCTION: u8 = 101;
+const IMAGE_SYM_CLASS_FILE: u8 = 103;
+const IMAGE_SYM_DTYPE_FUNCTION: u16 = 2;
+
+const SYMBOL_SIZE: usize = 18;
+
+pub enum AuxRecord {
+    /// follows a function definition
+    Function {
+        tag_index: u32,
+        total_size: u32,
+    },
+    /// follows a `.bf`/`.ef` symbol
+    BeginEnd {
+        line_number: u16,
+    },
+    /// follows a `.file` symbol
+    File(String),
+    /// follows a section symbol
+    Section {
+        length: u32,
+        number_of_relocations: u16,
+        checksum: u32,
+        number: u16,
+        selection: u8,
+    },
+    Other,
+}
+
+pub struct CoffSymbol {
+    pub name: String,
+    pub value: u32,
+    /// 1-based section index; 0 undefined, -1 absolute, -2 debug
+    pub section_number: i16,
+    pub sym_type: u16,
+    pub storage_class: u8,
+    pub aux: Vec<AuxRecord>,
+}
+
+/// File offset of the COFF string table, which starts right after the last symbol.
+pub fn string_table_offset(
+    mmap: &[u8],
+    pointer_to_symtab: u32,
+    number_of_symbols: u32,
+) -> Option<usize> {
+    let string_table = pointer_to_symtab as usize + number_of_symbols as usize * SYMBOL_SIZE;
+    (pointer_to_symtab != 0 && string_table + 4 <= mmap.len()).then_some(string_table)
+}
+
+pub fn get_string(mmap: &[u8], string_table: usize, offset: u32) -> String {
+    get_cstr(mmap, string_table + offset as usize)
+}
+
+impl CoffSymbol {
+    pub fn read_symbols(
+        mmap: &[u8],
+        pointer_to_symtab: u32,
+        number_of_symbols: u32,
+    ) -> Vec<CoffSymbol> {
+        let symtab = pointer_to_symtab as usize;
+        let string_table = match string_table_offset(mmap, pointer_to_symtab, number_of_symbols) {
+            Some(string_table) => string_table,
+            None => return Vec::new(),
+        };
+
+        let mut symbols = Vec::new();
+        let mut index = 0;
+        while index < number_of_symbols as usize {
+            let offset = symtab + index * SYMBOL_SIZE;
+            let name = match get_u32(mmap, offset) {
+                0 => get_string(mmap, string_table, get_u32(mmap, offset + 4)),
+                _ => Self::short_name(&mmap[offset..offset + 8]),
+            };
+            let storage_class = mmap[offset + 16];
+            let number_of_aux_symbols = mmap[offset + 17] as usize;
+            let mut symbol = CoffSymbol {
+                name,
+                value: get_u32(mmap, offset + 8),
+                section_number: get_u16(mmap, offset + 12) as i16,
+                sym_type: get_u16(mmap, offset + 14),
+                storage_class,
+                aux: Vec::new(),
+            };
+
+            let aux_end = (offset + (1 + number_of_aux_symbols) * SYMBOL_SIZE).min(string_table);
+            if storage_class == IMAGE_SYM_CLASS_FILE {
+                // the file name spans all of the aux records
+                symbol.aux.push(AuxRecord::File(Self::short_name(
+                    &mmap[offset + SYMBOL_SIZE..aux_end],
+                )));
+            } else {
+                for aux in (offset + SYMBOL_SIZE..aux_end).step_by(SYMBOL_SIZE) {
+                    symbol.aux.push(symbol.read_aux(mmap, aux));
+                }
+            }
+            symbols.push(symbol);
+            index += 1 + number_of_aux_symbols;
+        }
+
+        symbols
+    }
+
+    /// name stored inline, NUL padded to 8 bytes
+    pub fn short_name(bytes: &[u8]) -> String {
+        bytes
+            .iter()
+            .take_while(|b| **b != 0)
+            .map(|b| *b as char)
+            .collect()
+    }
+
+    fn read_aux(&self, mmap: &[u8], offset: usize) -> AuxRecord {
+        match self.storage_class {
+            IMAGE_SYM_CLASS_FUNCTION => AuxRecord::BeginEnd {
+                line_number: get_u16(mmap, offset + 4),
+            },
+            _ if self.is_function() && self.section_number > 0 => AuxRecord::Function {
+                tag_index: get_u32(mmap, offset),
+                total_size: get_u32(mmap, offset + 4),
+            },
+            IMAGE_SYM_CLASS_STATIC => AuxRecord::Section {
+                length: get_u32(mmap, offset),
+                number_of_relocations: get_u16(mmap, offset + 4),
+                checksum: get_u32(mmap, offset + 8),
+                number: get_u16(mmap, offset + 12),
+                selection: mmap[offset + 14],
+            },
+            _ => AuxRecord::Other,
+        }
+    }
+
+    fn section_to_str(&self) -> String {
+        match self.section_number {
+            0 => "UNDEF".to_string(),
+            -1 => "ABS".to_string(),
+            -2 => "DEBUG".to_string(),
+            n => format!("{n}"),
+        }
+    }
+
+    fn class_to_str(&self) -> &'static str {
+        match self.storage_class {
+            IMAGE_SYM_CLASS_EXTERNAL => "External",
+            IMAGE_SYM_CLASS_STATIC => "Static",
+            IMAGE_SYM_CLASS_FUNCTION => "Function",
+            IMAGE_SYM_CLASS_FILE => "Filename",
+            6 => "Label",
+            104 => "Section",
+            105 => "WeakExternal",
+            _ => "unknown class",
+        }
+    }
+
+    pub fn show(&self) {
+        let sym_type = if self.is_function() { "()" } else { "" };
+        println!(
+            "{:08x}  {:<6}{:<14}{}{}",
+            self.value,
+            self.section_to_str(),
+            self.class_to_str(),
+            self.name,
+            sym_type
+        );
+        for aux in self.aux.iter() {
+            match aux {
+                AuxRecord::Function {
+                    tag_index,
+                    total_size,
+                } => println!("    tag index {tag_index}, total size {total_size:#x}"),
+                AuxRecord::BeginEnd { line_number } => println!("    line {line_number}"),
+                AuxRecord::File(name) => println!("    file {name}"),
+                AuxRecord::Section {
+                    length,
+                    number_of_relocations,
+                    checksum,
+                    number,
+                    selection,
+                } => println!(
+                    "    length {length:#x}, relocations {number_of_relocations}, checksum {checksum:#x}, assoc {number}, comdat {selection}"
+                ),
+                AuxRecord::Other => {}
+            }
+        }
+    }
+
+    pub fn is_function(&self) -> bool {
+        (self.sym_type >> 4) & 0x3 == IMAGE_SYM_DTYPE_FUNCTION
+    }
+
+    /// TotalSize of the function definition aux record, 0 if unknown
+    pub fn function_size(&self) -> u32 {
+        self.aux
+            .iter()
+            .find_map(|aux| match aux {
+                AuxRecord::Function { total_size, .. } => Some(*total_size),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+}
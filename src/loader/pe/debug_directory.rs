@@ -0,0 +1,242 @@
+use crate::loader::{get_cstr, get_u16, get_u32};
+
+const IMAGE_DEBUG_TYPE_CODEVIEW: u32 = 2;
+const IMAGE_DEBUG_TYPE_VC_FEATURE: u32 = 12;
+const IMAGE_DEBUG_TYPE_POGO: u32 = 13;
+const IMAGE_DEBUG_TYPE_REPRO: u32 = 16;
+
+const CV_SIGNATURE_RSDS: &[u8] = b"RSDS";
+const CV_SIGNATURE_NB10: &[u8] = b"NB10";
+
+pub enum DebugData {
+    /// PDB 7.0 record, matched with its PDB by GUID and age
+    CodeView {
+        guid: [u8; 16],
+        age: u32,
+        pdb_path: String,
+    },
+    /// PDB 2.0 record, which has a timestamp instead of a GUID
+    CodeViewNb10 {
+        signature: u32,
+        age: u32,
+        pdb_path: String,
+    },
+    /// object and feature counts: Pre-VC++ 11.00, C/C++, /GS, /sdl, guardN
+    VcFeature([u32; 5]),
+    /// (rva, size, name) of the COFF groups the profile guided optimizer saw
+    Pogo {
+        signature: String,
+        groups: Vec<(u32, u32, String)>,
+    },
+    /// deterministic build hash, empty for `/Brepro` without a hash
+    Repro(Vec<u8>),
+    Other,
+}
+
+pub struct DebugEntry {
+    pub time_date_stamp: u32,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub debug_type: u32,
+    pub size_of_data: u32,
+    pub address_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub data: DebugData,
+}
+
+impl DebugData {
+    fn new(mmap: &[u8], debug_type: u32, offset: usize, size: usize) -> DebugData {
+        let raw = match mmap.get(offset..offset + size) {
+            Some(raw) if size != 0 => raw,
+            _ => return DebugData::Other,
+        };
+        match debug_type {
+            IMAGE_DEBUG_TYPE_CODEVIEW if size >= 24 && &raw[0..4] == CV_SIGNATURE_RSDS => {
+                let mut guid = [0; 16];
+                guid.copy_from_slice(&raw[4..20]);
+                DebugData::CodeView {
+                    guid,
+                    age: get_u32(raw, 20),
+                    pdb_path: get_cstr(raw, 24),
+                }
+            }
+            IMAGE_DEBUG_TYPE_CODEVIEW if size >= 16 && &raw[0..4] == CV_SIGNATURE_NB10 => {
+                DebugData::CodeViewNb10 {
+                    signature: get_u32(raw, 8),
+                    age: get_u32(raw, 12),
+                    pdb_path: get_cstr(raw, 16),
+                }
+            }
+            IMAGE_DEBUG_TYPE_VC_FEATURE if size >= 20 => {
+                DebugData::VcFeature([0, 4, 8, 12, 16].map(|i| get_u32(raw, i)))
+            }
+            IMAGE_DEBUG_TYPE_POGO if size >= 4 => {
+                let mut groups = Vec::new();
+                let mut group = 4;
+                while group + 8 < size {
+                    let name = get_cstr(raw, group + 8);
+                    groups.push((get_u32(raw, group), get_u32(raw, group + 4), name.clone()));
+                    // names are NUL terminated and padded to 4 bytes
+                    group = (group + 8 + name.len() + 1 + 3) & !3;
+                }
+                DebugData::Pogo {
+                    signature: raw[0..4]
+                        .iter()
+                        .rev()
+                        .take_while(|b| **b != 0)
+                        .map(|b| *b as char)
+                        .collect(),
+                    groups,
+                }
+            }
+            IMAGE_DEBUG_TYPE_REPRO if size >= 4 => {
+                let len = (get_u32(raw, 0) as usize).min(size - 4);
+                DebugData::Repro(raw[4..4 + len].to_vec())
+            }
+            _ => DebugData::Other,
+        }
+    }
+}
+
+impl DebugEntry {
+    /// IMAGE_DEBUG_DIRECTORY array of the debug data directory
+    pub fn read_all(mmap: &[u8], offset: usize, size: usize) -> Vec<DebugEntry> {
+        const ENTRY_SIZE: usize = 28;
+        (offset..offset + size - size % ENTRY_SIZE)
+            .step_by(ENTRY_SIZE)
+            .take_while(|entry| entry + ENTRY_SIZE <= mmap.len())
+            .map(|entry| {
+                let debug_type = get_u32(mmap, entry + 12);
+                let size_of_data = get_u32(mmap, entry + 16);
+                let pointer_to_raw_data = get_u32(mmap, entry + 24);
+                DebugEntry {
+                    time_date_stamp: get_u32(mmap, entry + 4),
+                    major_version: get_u16(mmap, entry + 8),
+                    minor_version: get_u16(mmap, entry + 10),
+                    debug_type,
+                    size_of_data,
+                    address_of_raw_data: get_u32(mmap, entry + 20),
+                    pointer_to_raw_data,
+                    data: DebugData::new(
+                        mmap,
+                        debug_type,
+                        pointer_to_raw_data as usize,
+                        size_of_data as usize,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// `{Data1-Data2-Data3-Data4}` with the first three fields little endian
+    pub fn format_guid(guid: &[u8; 16]) -> String {
+        format!(
+            "{:08X}-{:04X}-{:04X}-{}-{}",
+            get_u32(guid, 0),
+            get_u16(guid, 4),
+            get_u16(guid, 6),
+            guid[8..10]
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect::<String>(),
+            guid[10..16]
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect::<String>()
+        )
+    }
+
+    /// Directory name a symbol server stores the PDB under: GUID without dashes, then age.
+    pub fn pdb_key(&self) -> Option<String> {
+        match &self.data {
+            DebugData::CodeView { guid, age, .. } => Some(format!(
+                "{}{:X}",
+                Self::format_guid(guid).replace('-', ""),
+                age
+            )),
+            DebugData::CodeViewNb10 { signature, age, .. } => {
+                Some(format!("{signature:08X}{age:X}"))
+            }
+            _ => None,
+        }
+    }
+
+    fn type_to_str(&self) -> &'static str {
+        match self.debug_type {
+            1 => "COFF",
+            IMAGE_DEBUG_TYPE_CODEVIEW => "CODEVIEW",
+            3 => "FPO",
+            4 => "MISC",
+            5 => "EXCEPTION",
+            6 => "FIXUP",
+            7 => "OMAP_TO_SRC",
+            8 => "OMAP_FROM_SRC",
+            9 => "BORLAND",
+            11 => "CLSID",
+            IMAGE_DEBUG_TYPE_VC_FEATURE => "VC_FEATURE",
+            IMAGE_DEBUG_TYPE_POGO => "POGO",
+            14 => "ILTCG",
+            15 => "MPX",
+            IMAGE_DEBUG_TYPE_REPRO => "REPRO",
+            17 => "EMBEDDED_PDB",
+            19 => "PDBCHECKSUM",
+            20 => "EX_DLLCHARACTERISTICS",
+            _ => "UNKNOWN",
+        }
+    }
+
+    pub fn show(&self) {
+        println!(
+            "\n{:<12}time {:#010x}, version {}.{}, size {:#x}, rva {:#010x}, offset {:#010x}",
+            self.type_to_str(),
+            self.time_date_stamp,
+            self.major_version,
+            self.minor_version,
+            self.size_of_data,
+            self.address_of_raw_data,
+            self.pointer_to_raw_data
+        );
+        match &self.data {
+            DebugData::CodeView {
+                guid,
+                age,
+                pdb_path,
+            } => {
+                println!("    format:   RSDS");
+                println!("    GUID:     {{{}}}", Self::format_guid(guid));
+                println!("    age:      {age}");
+                println!("    PDB:      {pdb_path}");
+            }
+            DebugData::CodeViewNb10 {
+                signature,
+                age,
+                pdb_path,
+            } => {
+                println!("    format:   NB10");
+                println!("    signature: {signature:#010x}");
+                println!("    age:      {age}");
+                println!("    PDB:      {pdb_path}");
+            }
+            DebugData::VcFeature(counts) => {
+                let names = ["Pre-VC++ 11.00", "C/C++", "/GS", "/sdl", "guardN"];
+                for (name, count) in names.iter().zip(counts.iter()) {
+                    println!("    {:<16}{}", format!("{name}:"), count);
+                }
+            }
+            DebugData::Pogo { signature, groups } => {
+                println!("    signature: {signature}");
+                for (rva, size, name) in groups.iter() {
+                    println!("    {rva:#010x}  {size:#010x}  {name}");
+                }
+            }
+            DebugData::Repro(hash) => println!(
+                "    hash: {}",
+                hash.iter().map(|b| format!("{b:02x}")).collect::<String>()
+            ),
+            DebugData::Other => {}
+        }
+        if let Some(key) = self.pdb_key() {
+            println!("    symbol server key: {key}");
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::loader::pe::section_header::{rva_to_offset, SectionHeader};
+use crate::loader::{get_cstr, get_u16, get_u32};
+
+pub struct Export {
+    pub ordinal: u32,
+    pub rva: u32,
+    /// `None` for exports by ordinal only
+    pub name: Option<String>,
+    /// `DLL.Function` the loader resolves this export to instead of `rva`
+    pub forwarder: Option<String>,
+}
+
+pub struct ExportDirectory {
+    pub name: String,
+    pub ordinal_base: u32,
+    pub exports: Vec<Export>,
+}
+
+/// Number of `entry_size` byte entries between `rva` and the end of its section's raw data.
+fn entries_left(mmap: &[u8], sect_headers: &[SectionHeader], rva: u32, entry_size: usize) -> usize {
+    let section = sect_headers.iter().find(|s| {
+        (s.virtual_address
+            ..s.virtual_address
+                .saturating_add(s.virtual_size.max(s.size_of_raw_data)))
+            .contains(&rva)
+    });
+    match (section, rva_to_offset(sect_headers, rva)) {
+        (Some(s), Some(offset)) => {
+            let end =
+                (s.pointer_to_raw_data as usize + s.size_of_raw_data as usize).min(mmap.len());
+            end.saturating_sub(offset) / entry_size
+        }
+        _ => 0,
+    }
+}
+
+impl ExportDirectory {
+    pub fn new(
+        mmap: &[u8],
+        sect_headers: &[SectionHeader],
+        directory_rva: u32,
+        directory_size: u32,
+    ) -> Option<ExportDirectory> {
+        const DIRECTORY_SIZE: usize = 40;
+        let offset = rva_to_offset(sect_headers, directory_rva)
+            .filter(|offset| offset + DIRECTORY_SIZE <= mmap.len())?;
+        let name = get_u32(mmap, offset + 12);
+        let ordinal_base = get_u32(mmap, offset + 16);
+        let functions_rva = get_u32(mmap, offset + 28);
+        let names_rva = get_u32(mmap, offset + 32);
+        let name_ordinals_rva = get_u32(mmap, offset + 36);
+        // the counts are only trusted as far as the tables fit in their sections
+        let number_of_functions = (get_u32(mmap, offset + 20) as usize).min(entries_left(
+            mmap,
+            sect_headers,
+            functions_rva,
+            4,
+        ));
+        let number_of_names = (get_u32(mmap, offset + 24) as usize)
+            .min(entries_left(mmap, sect_headers, names_rva, 4))
+            .min(entries_left(mmap, sect_headers, name_ordinals_rva, 2));
+        let address_of_functions = rva_to_offset(sect_headers, functions_rva);
+        let address_of_names = rva_to_offset(sect_headers, names_rva);
+        let address_of_name_ordinals = rva_to_offset(sect_headers, name_ordinals_rva);
+
+        // the name table maps a name to an index into the function table
+        let mut names: Vec<Option<String>> = vec![None; number_of_functions];
+        if let (Some(address_of_names), Some(address_of_name_ordinals)) =
+            (address_of_names, address_of_name_ordinals)
+        {
+            for i in 0..number_of_names {
+                let index = get_u16(mmap, address_of_name_ordinals + i * 2) as usize;
+                let name = rva_to_offset(sect_headers, get_u32(mmap, address_of_names + i * 4));
+                if let (Some(slot), Some(name)) = (names.get_mut(index), name) {
+                    *slot = Some(get_cstr(mmap, name));
+                }
+            }
+        }
+
+        let forwarder_range = directory_rva..directory_rva.saturating_add(directory_size);
+        let exports = match address_of_functions {
+            Some(address_of_functions) => names
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, name)| {
+                    let rva = get_u32(mmap, address_of_functions + index * 4);
+                    if rva == 0 {
+                        return None;
+                    }
+                    // an rva pointing back into the directory is a forwarder string
+                    let forwarder = Some(rva)
+                        .filter(|rva| forwarder_range.contains(rva))
+                        .and_then(|rva| rva_to_offset(sect_headers, rva))
+                        .map(|offset| get_cstr(mmap, offset));
+                    Some(Export {
+                        ordinal: ordinal_base.wrapping_add(index as u32),
+                        rva,
+                        name,
+                        forwarder,
+                    })
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Some(ExportDirectory {
+            name: rva_to_offset(sect_headers, name).map_or(String::new(), |n| get_cstr(mmap, n)),
+            ordinal_base,
+            exports,
+        })
+    }
+
+    pub fn show(&self) {
+        println!("=== exports of {} ===", self.name);
+        println!("ordinal base: {}", self.ordinal_base);
+        println!("{:<10}{:<12}name", "ordinal", "rva");
+        for export in self.exports.iter() {
+            let name = export.name.as_deref().unwrap_or("[NONAME]");
+            match &export.forwarder {
+                Some(forwarder) => println!(
+                    "{:<10}{:#010x}  {} -> {}",
+                    export.ordinal, export.rva, name, forwarder
+                ),
+                None => println!("{:<10}{:#010x}  {}", export.ordinal, export.rva, name),
+            }
+        }
+    }
+}
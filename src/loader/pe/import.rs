@@ -0,0 +1,186 @@
+use crate::loader::pe::section_header::{rva_to_offset, SectionHeader};
+use crate::loader::{get_cstr, get_u16, get_u32, get_u64};
+
+pub struct ImportedFunction {
+    /// `None` for imports by ordinal
+    pub name: Option<String>,
+    pub hint: u16,
+    pub ordinal: Option<u16>,
+    /// rva of the IAT slot the loader patches with the function address
+    pub iat_rva: u32,
+}
+
+pub struct ImportedDll {
+    pub name: String,
+    pub delay: bool,
+    pub functions: Vec<ImportedFunction>,
+}
+
+impl ImportedFunction {
+    pub fn label(&self, dll: &str) -> String {
+        match (&self.name, self.ordinal) {
+            (Some(name), _) => name.clone(),
+            (None, Some(ordinal)) => format!("{dll}#{ordinal}"),
+            (None, None) => format!("{dll}#?"),
+        }
+    }
+}
+
+impl ImportedDll {
+    /// Walks the Import Name Table (or the IAT when the INT is missing) of one descriptor.
+    fn read_thunks(
+        mmap: &[u8],
+        bitness: u32,
+        sect_headers: &[SectionHeader],
+        int_rva: u32,
+        iat_rva: u32,
+    ) -> Vec<ImportedFunction> {
+        let thunk_size: u32 = if bitness == 64 { 8 } else { 4 };
+        let table_rva = if int_rva != 0 { int_rva } else { iat_rva };
+        let mut table = match rva_to_offset(sect_headers, table_rva) {
+            Some(offset) => offset,
+            None => return Vec::new(),
+        };
+
+        let mut functions = Vec::new();
+        let mut slot_rva = iat_rva;
+        while table + thunk_size as usize <= mmap.len() {
+            let (thunk, by_ordinal) = match bitness {
+                64 => {
+                    let thunk = get_u64(mmap, table);
+                    (thunk, thunk >> 63 == 1)
+                }
+                _ => {
+                    let thunk = get_u32(mmap, table) as u64;
+                    (thunk, thunk >> 31 == 1)
+                }
+            };
+            if thunk == 0 {
+                break;
+            }
+
+            let function = if by_ordinal {
+                ImportedFunction {
+                    name: None,
+                    hint: 0,
+                    ordinal: Some(thunk as u16),
+                    iat_rva: slot_rva,
+                }
+            } else {
+                let hint_name = rva_to_offset(sect_headers, thunk as u32);
+                ImportedFunction {
+                    name: hint_name.map(|offset| get_cstr(mmap, offset + 2)),
+                    hint: hint_name.map_or(0, |offset| get_u16(mmap, offset)),
+                    ordinal: None,
+                    iat_rva: slot_rva,
+                }
+            };
+            functions.push(function);
+            table += thunk_size as usize;
+            slot_rva += thunk_size;
+        }
+
+        functions
+    }
+
+    /// IMAGE_IMPORT_DESCRIPTOR table of the import directory
+    pub fn read_imports(
+        mmap: &[u8],
+        bitness: u32,
+        sect_headers: &[SectionHeader],
+        directory_rva: u32,
+    ) -> Vec<ImportedDll> {
+        const DESCRIPTOR_SIZE: usize = 20;
+        let mut dlls = Vec::new();
+        let mut descriptor = match rva_to_offset(sect_headers, directory_rva) {
+            Some(offset) => offset,
+            None => return dlls,
+        };
+
+        while descriptor + DESCRIPTOR_SIZE <= mmap.len() {
+            let original_first_thunk = get_u32(mmap, descriptor);
+            let name = get_u32(mmap, descriptor + 12);
+            let first_thunk = get_u32(mmap, descriptor + 16);
+            if name == 0 && first_thunk == 0 {
+                break;
+            }
+
+            dlls.push(ImportedDll {
+                name: rva_to_offset(sect_headers, name)
+                    .map_or(String::new(), |offset| get_cstr(mmap, offset)),
+                delay: false,
+                functions: Self::read_thunks(
+                    mmap,
+                    bitness,
+                    sect_headers,
+                    original_first_thunk,
+                    first_thunk,
+                ),
+            });
+            descriptor += DESCRIPTOR_SIZE;
+        }
+
+        dlls
+    }
+
+    /// ImgDelayDescr table of the delay import directory
+    pub fn read_delay_imports(
+        mmap: &[u8],
+        bitness: u32,
+        image_base: u64,
+        sect_headers: &[SectionHeader],
+        directory_rva: u32,
+    ) -> Vec<ImportedDll> {
+        const DESCRIPTOR_SIZE: usize = 32;
+        const DLATTR_RVA: u32 = 1;
+        let mut dlls = Vec::new();
+        let mut descriptor = match rva_to_offset(sect_headers, directory_rva) {
+            Some(offset) => offset,
+            None => return dlls,
+        };
+
+        while descriptor + DESCRIPTOR_SIZE <= mmap.len() {
+            let attributes = get_u32(mmap, descriptor);
+            // descriptors from old linkers hold VAs instead of RVAs
+            let to_rva = |value: u32| match attributes & DLATTR_RVA {
+                0 if value != 0 => (value as u64).wrapping_sub(image_base) as u32,
+                _ => value,
+            };
+            let name = to_rva(get_u32(mmap, descriptor + 4));
+            let iat = to_rva(get_u32(mmap, descriptor + 12));
+            let int = to_rva(get_u32(mmap, descriptor + 16));
+            if name == 0 && iat == 0 {
+                break;
+            }
+
+            dlls.push(ImportedDll {
+                name: rva_to_offset(sect_headers, name)
+                    .map_or(String::new(), |offset| get_cstr(mmap, offset)),
+                delay: true,
+                functions: Self::read_thunks(mmap, bitness, sect_headers, int, iat),
+            });
+            descriptor += DESCRIPTOR_SIZE;
+        }
+
+        dlls
+    }
+
+    pub fn show(&self, image_base: u64) {
+        let kind = if self.delay { "delay import" } else { "import" };
+        println!("\n=== {} ({kind}) ===", self.name);
+        println!("{:<20}{:<8}name", "iat", "hint");
+        for function in self.functions.iter() {
+            let name = match (&function.name, function.ordinal) {
+                (Some(name), _) => name.clone(),
+                (None, Some(ordinal)) => format!("ordinal {ordinal}"),
+                (None, None) => String::new(),
+            };
+            println!(
+                "{:#018x}  {:<8}{}",
+                image_base + function.iat_rva as u64,
+                function.hint,
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::loader::{get_u32, get_u64};
+
+/// The parts of IMAGE_LOAD_CONFIG_DIRECTORY used by the mitigation report.
+/// Fields the directory is too short to contain are left at 0.
+pub struct LoadConfig {
+    pub security_cookie: u64,
+    pub se_handler_table: u64,
+    pub se_handler_count: u64,
+    pub guard_cf_function_table: u64,
+    pub guard_cf_function_count: u64,
+    pub guard_flags: u32,
+}
+
+impl LoadConfig {
+    pub fn new(mmap: &[u8], offset: usize, bitness: u32) -> LoadConfig {
+        let size = get_u32(mmap, offset);
+        let (word, base) = match bitness {
+            64 => (8, 88),
+            _ => (4, 60),
+        };
+        let field = |index: usize| {
+            let field_off = base + index * word;
+            if field_off + word > size as usize {
+                0
+            } else if word == 8 {
+                get_u64(mmap, offset + field_off)
+            } else {
+                get_u32(mmap, offset + field_off) as u64
+            }
+        };
+        let guard_flags_off = base + 7 * word;
+
+        LoadConfig {
+            security_cookie: field(0),
+            se_handler_table: field(1),
+            se_handler_count: field(2),
+            guard_cf_function_table: field(5),
+            guard_cf_function_count: field(6),
+            guard_flags: if guard_flags_off + 4 > size as usize {
+                0
+            } else {
+                get_u32(mmap, offset + guard_flags_off)
+            },
+        }
+    }
+}
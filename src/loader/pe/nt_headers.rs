@@ -1,9 +1,51 @@
-use crate::loader::{get_u16, get_u32, get_u64};
+use crate::disassembler::arm::ArmState;
+use crate::disassembler::Isa;
+use crate::loader::{get_u16, get_u32, get_u64, Endian};
+
+const PE32PLUS_MAGIC: u16 = 0x20b;
+
+pub const IMAGE_DIRECTORY_ENTRY_EXPORT: usize = 0;
+pub const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+pub const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+pub const IMAGE_DIRECTORY_ENTRY_EXCEPTION: usize = 3;
+pub const IMAGE_DIRECTORY_ENTRY_SECURITY: usize = 4;
+pub const IMAGE_DIRECTORY_ENTRY_BASERELOC: usize = 5;
+pub const IMAGE_DIRECTORY_ENTRY_DEBUG: usize = 6;
+pub const IMAGE_DIRECTORY_ENTRY_ARCHITECTURE: usize = 7;
+pub const IMAGE_DIRECTORY_ENTRY_GLOBALPTR: usize = 8;
+pub const IMAGE_DIRECTORY_ENTRY_TLS: usize = 9;
+pub const IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG: usize = 10;
+pub const IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT: usize = 11;
+pub const IMAGE_DIRECTORY_ENTRY_IAT: usize = 12;
+pub const IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT: usize = 13;
+pub const IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR: usize = 14;
+
+fn get_directory_name(index: usize) -> &'static str {
+    match index {
+        IMAGE_DIRECTORY_ENTRY_EXPORT => "export",
+        IMAGE_DIRECTORY_ENTRY_IMPORT => "import",
+        IMAGE_DIRECTORY_ENTRY_RESOURCE => "resource",
+        IMAGE_DIRECTORY_ENTRY_EXCEPTION => "exception",
+        IMAGE_DIRECTORY_ENTRY_SECURITY => "security",
+        IMAGE_DIRECTORY_ENTRY_BASERELOC => "basereloc",
+        IMAGE_DIRECTORY_ENTRY_DEBUG => "debug",
+        IMAGE_DIRECTORY_ENTRY_ARCHITECTURE => "architecture",
+        IMAGE_DIRECTORY_ENTRY_GLOBALPTR => "globalptr",
+        IMAGE_DIRECTORY_ENTRY_TLS => "TLS",
+        IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG => "load config",
+        IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT => "bound import",
+        IMAGE_DIRECTORY_ENTRY_IAT => "IAT",
+        IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT => "delay import",
+        IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR => "CLR",
+        _ => "reserved",
+    }
+}
+
 pub struct NtHeader {
     offset: usize,
     signature: u32,
     file_header: FileHeader,
-    optional_header: OptionalHeader,
+    pub optional_header: OptionalHeader,
 }
 
 struct FileHeader {
@@ -16,39 +58,46 @@ struct FileHeader {
     characteristics: u16,
 }
 
-struct OptionalHeader {
-    magic: u16,
-    major_linker_version: u8,
-    minor_linker_version: u8,
-    size_of_code: u32,
-    size_of_initialized_data: u32,
-    size_of_uninitialized_data: u32,
-    address_of_entry_point: u32,
-    base_of_code: u32,
-    base_of_data: u32,
-
-    image_base: u32,
-    section_alignment: u32,
-    file_alignment: u32,
-    major_operating_system_version: u32,
-    minor_operating_system_version: u32,
-    major_image_version: u16,
-    minor_image_version: u16,
-    major_subsystem_version: u16,
-    minor_subsystem_version: u16,
-    win32_version_value: u32,
-    size_of_image: u32,
-    size_of_headers: u32,
-    check_sum: u32,
-    subsystem: u16,
-    dll_characteristics: u16,
-    size_of_stack_reserve: u64,
-    size_of_stack_commit: u64,
-    size_of_heap_reserve: u64,
-    size_of_heap_commit: u64,
-    loader_flags: u32,
-    number_of_rva_and_sizes: u32,
-    _data_directory: u32,
+pub struct DataDirectory {
+    /// file offset rather than RVA for the security directory
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+pub struct OptionalHeader {
+    pub magic: u16,
+    pub major_linker_version: u8,
+    pub minor_linker_version: u8,
+    pub size_of_code: u32,
+    pub size_of_initialized_data: u32,
+    pub size_of_uninitialized_data: u32,
+    pub address_of_entry_point: u32,
+    pub base_of_code: u32,
+    /// only present in PE32
+    pub base_of_data: Option<u32>,
+
+    pub image_base: u64,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub major_operating_system_version: u16,
+    pub minor_operating_system_version: u16,
+    pub major_image_version: u16,
+    pub minor_image_version: u16,
+    pub major_subsystem_version: u16,
+    pub minor_subsystem_version: u16,
+    pub win32_version_value: u32,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub check_sum: u32,
+    pub subsystem: u16,
+    pub dll_characteristics: u16,
+    pub size_of_stack_reserve: u64,
+    pub size_of_stack_commit: u64,
+    pub size_of_heap_reserve: u64,
+    pub size_of_heap_commit: u64,
+    pub loader_flags: u32,
+    pub number_of_rva_and_sizes: u32,
+    pub data_directories: Vec<DataDirectory>,
 }
 
 impl NtHeader {
@@ -76,6 +125,67 @@ impl NtHeader {
         self.file_header.number_of_sections as usize
     }
 
+    pub fn bitness(&self) -> u32 {
+        match self.optional_header.magic {
+            PE32PLUS_MAGIC => 64,
+            _ => 32,
+        }
+    }
+
+    pub fn isa(&self) -> Isa {
+        const IMAGE_FILE_MACHINE_I386: u16 = 0x14c;
+        const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x1c4;
+        const IMAGE_FILE_MACHINE_RISCV32: u16 = 0x5032;
+        const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+        const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+        const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+        match self.file_header.machine {
+            IMAGE_FILE_MACHINE_I386 => Isa::X86(32),
+            IMAGE_FILE_MACHINE_AMD64 => Isa::X86(64),
+            IMAGE_FILE_MACHINE_ARMNT => Isa::Arm(ArmState::Thumb, Endian::Little),
+            IMAGE_FILE_MACHINE_ARM64 => Isa::AArch64,
+            IMAGE_FILE_MACHINE_RISCV32 => Isa::RiscV(32),
+            IMAGE_FILE_MACHINE_RISCV64 => Isa::RiscV(64),
+            _ => Isa::X86(self.bitness()),
+        }
+    }
+
+    pub fn machine(&self) -> u16 {
+        self.file_header.machine
+    }
+
+    pub fn address_of_entry_point(&self) -> u32 {
+        self.optional_header.address_of_entry_point
+    }
+
+    pub fn image_base(&self) -> u64 {
+        self.optional_header.image_base
+    }
+
+    /// (pointer_to_symtab, number_of_symbols) of the COFF symbol table
+    pub fn symbol_table(&self) -> (u32, u32) {
+        (
+            self.file_header.pointer_to_symtab,
+            self.file_header.number_of_symbols,
+        )
+    }
+
+    pub fn characteristics(&self) -> u16 {
+        self.file_header.characteristics
+    }
+
+    pub fn dll_characteristics(&self) -> u16 {
+        self.optional_header.dll_characteristics
+    }
+
+    /// `None` if the directory is absent or empty
+    pub fn data_directory(&self, index: usize) -> Option<&DataDirectory> {
+        self.optional_header
+            .data_directories
+            .get(index)
+            .filter(|d| d.virtual_address != 0 && d.size != 0)
+    }
+
     pub fn show(&self) {
         println!("\n=== NtHeader ===");
         println!("signature: {:#x}", self.signature);
@@ -114,8 +224,28 @@ impl FileHeader {
 
 impl OptionalHeader {
     pub fn new(mmap: &[u8], offset: usize) -> OptionalHeader {
+        let magic = get_u16(mmap, offset);
+        // PE32+ drops base_of_data and widens image_base and the stack/heap sizes to 64 bits
+        let is_pe32plus = magic == PE32PLUS_MAGIC;
+        let get_word = |index: usize| match is_pe32plus {
+            true => get_u64(mmap, index),
+            false => get_u32(mmap, index) as u64,
+        };
+        let word_size = if is_pe32plus { 8 } else { 4 };
+        let sizes_offset = offset + 72;
+        let loader_flags_offset = sizes_offset + 4 * word_size;
+        let data_directory_offset = loader_flags_offset + 8;
+
+        let number_of_rva_and_sizes = get_u32(mmap, loader_flags_offset + 4);
+        // the array never has more than 16 entries, whatever the header claims
+        let data_directories = (0..number_of_rva_and_sizes.min(16) as usize)
+            .map(|i| DataDirectory {
+                virtual_address: get_u32(mmap, data_directory_offset + i * 8),
+                size: get_u32(mmap, data_directory_offset + i * 8 + 4),
+            })
+            .collect();
         OptionalHeader {
-            magic: get_u16(mmap, offset),
+            magic,
             major_linker_version: mmap[offset + 2],
             minor_linker_version: mmap[offset + 3],
             size_of_code: get_u32(mmap, offset + 4),
@@ -123,12 +253,15 @@ impl OptionalHeader {
             size_of_uninitialized_data: get_u32(mmap, offset + 12),
             address_of_entry_point: get_u32(mmap, offset + 16),
             base_of_code: get_u32(mmap, offset + 20),
-            base_of_data: get_u32(mmap, offset + 24),
-            image_base: get_u32(mmap, offset + 28),
+            base_of_data: (!is_pe32plus).then(|| get_u32(mmap, offset + 24)),
+            image_base: match is_pe32plus {
+                true => get_u64(mmap, offset + 24),
+                false => get_u32(mmap, offset + 28) as u64,
+            },
             section_alignment: get_u32(mmap, offset + 32),
             file_alignment: get_u32(mmap, offset + 36),
-            major_operating_system_version: get_u32(mmap, offset + 40),
-            minor_operating_system_version: get_u32(mmap, offset + 42),
+            major_operating_system_version: get_u16(mmap, offset + 40),
+            minor_operating_system_version: get_u16(mmap, offset + 42),
             major_image_version: get_u16(mmap, offset + 44),
             minor_image_version: get_u16(mmap, offset + 46),
             major_subsystem_version: get_u16(mmap, offset + 48),
@@ -139,13 +272,13 @@ impl OptionalHeader {
             check_sum: get_u32(mmap, offset + 64),
             subsystem: get_u16(mmap, offset + 68),
             dll_characteristics: get_u16(mmap, offset + 70),
-            size_of_stack_reserve: get_u64(mmap, offset + 72),
-            size_of_stack_commit: get_u64(mmap, offset + 80),
-            size_of_heap_reserve: get_u64(mmap, offset + 88),
-            size_of_heap_commit: get_u64(mmap, offset + 96),
-            loader_flags: get_u32(mmap, offset + 104),
-            number_of_rva_and_sizes: get_u32(mmap, offset + 108),
-            _data_directory: get_u32(mmap, offset + 112),
+            size_of_stack_reserve: get_word(sizes_offset),
+            size_of_stack_commit: get_word(sizes_offset + word_size),
+            size_of_heap_reserve: get_word(sizes_offset + 2 * word_size),
+            size_of_heap_commit: get_word(sizes_offset + 3 * word_size),
+            loader_flags: get_u32(mmap, loader_flags_offset),
+            number_of_rva_and_sizes,
+            data_directories,
         }
     }
 
@@ -168,7 +301,9 @@ impl OptionalHeader {
             self.address_of_entry_point
         );
         println!("base_of_code:\t{:#x}", self.base_of_code);
-        println!("base_of_data:\t{:#x}", self.base_of_data);
+        if let Some(base_of_data) = self.base_of_data {
+            println!("base_of_data:\t{base_of_data:#x}");
+        }
 
         println!("image_base:\t{:#x}", self.image_base);
         println!("section_alignment:\t{:#x}", self.section_alignment);
@@ -206,6 +341,14 @@ impl OptionalHeader {
             "number_of_rva_and_sizes:\t{:#x}",
             self.number_of_rva_and_sizes
         );
-        println!("_data_directory:\t{:#x}", self._data_directory);
+        println!("--- DataDirectory ---");
+        for (index, dir) in self.data_directories.iter().enumerate() {
+            println!(
+                "{:<16}rva: {:#010x}\tsize: {:#x}",
+                get_directory_name(index),
+                dir.virtual_address,
+                dir.size
+            );
+        }
     }
 }
@@ -0,0 +1,262 @@
+use crate::loader::pe::section_header::{rva_to_offset, SectionHeader};
+use crate::loader::{get_u16, get_u32};
+
+pub const RT_VERSION: u32 = 16;
+pub const RT_MANIFEST: u32 = 24;
+
+const VS_FFI_SIGNATURE: u32 = 0xfeef04bd;
+
+#[derive(Clone, PartialEq)]
+pub enum ResourceId {
+    Id(u32),
+    Name(String),
+}
+
+/// A leaf of the type/name/language tree.
+pub struct Resource {
+    pub type_id: ResourceId,
+    pub name: ResourceId,
+    pub language: u32,
+    pub data_rva: u32,
+    pub size: u32,
+    pub code_page: u32,
+}
+
+pub struct VersionInfo {
+    pub file_version: String,
+    pub product_version: String,
+    /// key/value pairs of every StringTable, e.g. ("CompanyName", "...")
+    pub strings: Vec<(String, String)>,
+}
+
+/// UTF-16 string of `len` code units, or up to the terminating NUL
+fn get_utf16(mmap: &[u8], offset: usize, len: Option<usize>) -> String {
+    let units = (offset..mmap.len().saturating_sub(1))
+        .step_by(2)
+        .map(|i| get_u16(mmap, i));
+    let units = match len {
+        Some(len) => units.take(len).collect::<Vec<u16>>(),
+        None => units.take_while(|u| *u != 0).collect::<Vec<u16>>(),
+    };
+    String::from_utf16_lossy(&units)
+}
+
+impl ResourceId {
+    fn new(mmap: &[u8], root: usize, name: u32) -> ResourceId {
+        match name >> 31 {
+            // IMAGE_RESOURCE_DIR_STRING_U, a counted UTF-16 string
+            1 => {
+                let offset = root + (name & 0x7fff_ffff) as usize;
+                ResourceId::Name(get_utf16(
+                    mmap,
+                    offset + 2,
+                    Some(get_u16(mmap, offset) as usize),
+                ))
+            }
+            _ => ResourceId::Id(name),
+        }
+    }
+
+    fn type_to_str(&self) -> &str {
+        match self {
+            ResourceId::Id(id) => match *id {
+                1 => "CURSOR",
+                2 => "BITMAP",
+                3 => "ICON",
+                4 => "MENU",
+                5 => "DIALOG",
+                6 => "STRING",
+                7 => "FONTDIR",
+                8 => "FONT",
+                9 => "ACCELERATOR",
+                10 => "RCDATA",
+                11 => "MESSAGETABLE",
+                12 => "GROUP_CURSOR",
+                14 => "GROUP_ICON",
+                RT_VERSION => "VERSION",
+                17 => "DLGINCLUDE",
+                19 => "PLUGPLAY",
+                20 => "VXD",
+                21 => "ANICURSOR",
+                22 => "ANIICON",
+                23 => "HTML",
+                RT_MANIFEST => "MANIFEST",
+                _ => "",
+            },
+            ResourceId::Name(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ResourceId::Id(id) => write!(f, "{id}"),
+            ResourceId::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Resource {
+    /// (id, OffsetToData) of the entries of the IMAGE_RESOURCE_DIRECTORY at `offset`
+    fn read_directory(mmap: &[u8], root: usize, offset: usize) -> Vec<(ResourceId, u32)> {
+        const DIRECTORY_SIZE: usize = 16;
+        const ENTRY_SIZE: usize = 8;
+        if offset + DIRECTORY_SIZE > mmap.len() {
+            return Vec::new();
+        }
+        let number_of_entries =
+            get_u16(mmap, offset + 12) as usize + get_u16(mmap, offset + 14) as usize;
+
+        (offset + DIRECTORY_SIZE..offset + DIRECTORY_SIZE + number_of_entries * ENTRY_SIZE)
+            .step_by(ENTRY_SIZE)
+            .take_while(|entry| entry + ENTRY_SIZE <= mmap.len())
+            .map(|entry| {
+                (
+                    ResourceId::new(mmap, root, get_u32(mmap, entry)),
+                    get_u32(mmap, entry + 4),
+                )
+            })
+            .collect()
+    }
+
+    /// Walks the type, name and language levels below the root directory at `root`.
+    pub fn read_all(mmap: &[u8], root: usize) -> Vec<Resource> {
+        const SUBDIRECTORY: u32 = 0x8000_0000;
+        let subdirectories = |offset: usize| {
+            Self::read_directory(mmap, root, offset)
+                .into_iter()
+                .filter(|(_, data)| data & SUBDIRECTORY != 0)
+                .map(|(id, data)| (id, root + (data & !SUBDIRECTORY) as usize))
+                .collect::<Vec<(ResourceId, usize)>>()
+        };
+
+        let mut resources = Vec::new();
+        for (type_id, type_dir) in subdirectories(root) {
+            for (name, name_dir) in subdirectories(type_dir) {
+                for (language, data) in Self::read_directory(mmap, root, name_dir) {
+                    // leaves point to an IMAGE_RESOURCE_DATA_ENTRY
+                    let data = match data & SUBDIRECTORY {
+                        0 if root + data as usize + 16 <= mmap.len() => root + data as usize,
+                        _ => continue,
+                    };
+                    resources.push(Resource {
+                        type_id: type_id.clone(),
+                        name: name.clone(),
+                        language: match language {
+                            ResourceId::Id(id) => id,
+                            ResourceId::Name(_) => 0,
+                        },
+                        data_rva: get_u32(mmap, data),
+                        size: get_u32(mmap, data + 4),
+                        code_page: get_u32(mmap, data + 8),
+                    });
+                }
+            }
+        }
+
+        resources
+    }
+
+    /// `type/name/language`, the form `--save-resource` takes
+    pub fn path(&self) -> String {
+        format!("{}/{}/{}", self.type_id, self.name, self.language)
+    }
+
+    pub fn data<'a>(&self, mmap: &'a [u8], sect_headers: &[SectionHeader]) -> Option<&'a [u8]> {
+        let offset = rva_to_offset(sect_headers, self.data_rva)?;
+        mmap.get(offset..offset + self.size as usize)
+    }
+
+    pub fn show(&self) {
+        println!(
+            "{:<24}{:<14}{:#010x}  {:<10}{}",
+            self.path(),
+            self.type_id.type_to_str(),
+            self.data_rva,
+            self.size,
+            self.code_page
+        );
+    }
+}
+
+impl VersionInfo {
+    /// Header shared by every block of VS_VERSIONINFO:
+    /// (wLength, wValueLength, szKey, offset of the value)
+    fn read_block(data: &[u8], offset: usize) -> (usize, usize, String, usize) {
+        let length = get_u16(data, offset) as usize;
+        let value_length = get_u16(data, offset + 2) as usize;
+        let key = get_utf16(data, offset + 6, None);
+        // the key is NUL terminated and the value starts 32 bit aligned
+        let value = (offset + 6 + (key.encode_utf16().count() + 1) * 2 + 3) & !3;
+        (length, value_length, key, value)
+    }
+
+    /// Offsets of the child blocks between `start` and `end`.
+    fn children(data: &[u8], start: usize, end: usize) -> Vec<usize> {
+        let mut children = Vec::new();
+        let mut child = (start + 3) & !3;
+        while child + 6 <= end {
+            let length = get_u16(data, child) as usize;
+            if length == 0 {
+                break;
+            }
+            children.push(child);
+            child = (child + length + 3) & !3;
+        }
+        children
+    }
+
+    pub fn new(data: &[u8]) -> Option<VersionInfo> {
+        let (length, value_length, key, value) = Self::read_block(data, 0);
+        if key != "VS_VERSION_INFO" || length > data.len() {
+            return None;
+        }
+
+        let version =
+            |ms: u32, ls: u32| format!("{}.{}.{}.{}", ms >> 16, ms & 0xffff, ls >> 16, ls & 0xffff);
+        let (file_version, product_version) =
+            match value_length >= 52 && get_u32(data, value) == VS_FFI_SIGNATURE {
+                true => (
+                    version(get_u32(data, value + 8), get_u32(data, value + 12)),
+                    version(get_u32(data, value + 16), get_u32(data, value + 20)),
+                ),
+                false => (String::new(), String::new()),
+            };
+
+        // StringFileInfo -> StringTable per language -> String
+        let mut strings = Vec::new();
+        for file_info in Self::children(data, value + value_length, length) {
+            let (info_length, _, info_key, info_value) = Self::read_block(data, file_info);
+            if info_key != "StringFileInfo" {
+                continue;
+            }
+            for table in Self::children(data, info_value, file_info + info_length) {
+                let (table_length, _, _, table_value) = Self::read_block(data, table);
+                for string in Self::children(data, table_value, table + table_length) {
+                    let (_, string_length, key, value) = Self::read_block(data, string);
+                    let value = match string_length {
+                        0 => String::new(),
+                        _ => get_utf16(data, value, None),
+                    };
+                    strings.push((key, value));
+                }
+            }
+        }
+
+        Some(VersionInfo {
+            file_version,
+            product_version,
+            strings,
+        })
+    }
+
+    pub fn show(&self) {
+        println!("--- version info ---");
+        println!("{:<20}{}", "FILEVERSION", self.file_version);
+        println!("{:<20}{}", "PRODUCTVERSION", self.product_version);
+        for (key, value) in self.strings.iter() {
+            println!("{:<20}{}", key, value);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::loader::get_u32;
+
+const RICH_SIGNATURE: u32 = 0x68636952; // "Rich"
+const DANS_SIGNATURE: u32 = 0x536e6144; // "DanS"
+
+pub struct RichEntry {
+    pub product_id: u16,
+    pub build: u16,
+    /// number of objects the tool produced, or of imported functions for Import0
+    pub count: u32,
+}
+
+/// The XOR encoded "Rich" header MSVC's linker writes between the DOS stub and the NT headers.
+pub struct RichHeader {
+    /// file offset of the encoded "DanS" marker
+    pub offset: usize,
+    pub key: u32,
+    pub entries: Vec<RichEntry>,
+    /// checksum recomputed over the DOS header, stub and entries
+    pub checksum: u32,
+}
+
+/// Tools of a toolset, in product id order.
+const TOOLS: [&str; 7] = [
+    "AliasObj", "Cvtpgd", "Cvtres", "Export", "Implib", "Linker", "Masm",
+];
+
+/// Compiler (Utc) variants of a toolset, in product id order.
+const UTC_VARIANTS: [&str; 11] = [
+    "C",
+    "CPP",
+    "CVTCIL_C",
+    "CVTCIL_CPP",
+    "LTCG_C",
+    "LTCG_CPP",
+    "LTCG_MSIL",
+    "POGO_I_C",
+    "POGO_I_CPP",
+    "POGO_O_C",
+    "POGO_O_CPP",
+];
+
+/// Compiler variants of Visual Studio 2008, which come before its tools.
+const UTC1500_VARIANTS: [&str; 13] = [
+    "C",
+    "CPP",
+    "C_Std",
+    "CPP_Std",
+    "CVTCIL_C",
+    "CVTCIL_CPP",
+    "LTCG_C",
+    "LTCG_CPP",
+    "LTCG_MSIL",
+    "POGO_I_C",
+    "POGO_I_CPP",
+    "POGO_O_C",
+    "POGO_O_CPP",
+];
+
+/// (first product id, tools version, compiler versions, release) of the toolsets that list
+/// their tools followed by the variants of each compiler.
+const TOOLSETS: [(u16, &str, &[&str], &str); 6] = [
+    (
+        0x0098,
+        "1000",
+        &["Phx1600", "Utc1600"],
+        " (Visual Studio 2010)",
+    ),
+    (0x00b5, "1010", &["Utc1610"], " (Visual Studio 2010 SP1)"),
+    (0x00c7, "1100", &["Utc1700"], " (Visual Studio 2012)"),
+    (0x00d9, "1200", &["Utc1800"], " (Visual Studio 2013)"),
+    (0x00eb, "1210", &["Utc1810"], ""),
+    (0x00fd, "1400", &["Utc1900"], ""),
+];
+
+impl RichEntry {
+    /// Products from Visual Studio 2008 on are named. Visual Studio 2015 and later share
+    /// their ids and are told apart by build number.
+    fn tool_name(&self) -> String {
+        let id = self.product_id;
+        match id {
+            0x0000 => return "Unmarked".to_string(),
+            0x0001 => return "Import0".to_string(),
+            0x0083..=0x008f => {
+                let variant = UTC1500_VARIANTS[(id - 0x0083) as usize];
+                return format!("Utc1500_{variant} (Visual Studio 2008)");
+            }
+            0x0090..=0x0096 => {
+                let tool = match id {
+                    0x0090 => "Cvtpgd1500",
+                    0x0091 => "Linker900",
+                    0x0092 => "Export900",
+                    0x0093 => "Implib900",
+                    0x0094 => "Cvtres900",
+                    0x0095 => "Masm900",
+                    _ => "AliasObj900",
+                };
+                return format!("{tool} (Visual Studio 2008)");
+            }
+            _ => {}
+        }
+
+        let toolset = TOOLSETS.iter().find(|(first, _, compilers, _)| {
+            (*first..*first + (TOOLS.len() + compilers.len() * UTC_VARIANTS.len()) as u16)
+                .contains(&id)
+        });
+        let (first, tools_version, compilers, release) = match toolset {
+            Some(toolset) => *toolset,
+            None => return format!("unknown ({id:#06x})"),
+        };
+        let index = (id - first) as usize;
+        let name = match index.checked_sub(TOOLS.len()) {
+            // Cvtpgd is versioned like the compiler
+            None if TOOLS[index] == "Cvtpgd" => format!("Cvtpgd{}", &compilers[0][3..]),
+            None => format!("{}{tools_version}", TOOLS[index]),
+            Some(index) => format!(
+                "{}_{}",
+                compilers[index / UTC_VARIANTS.len()],
+                UTC_VARIANTS[index % UTC_VARIANTS.len()]
+            ),
+        };
+        let release = match self.build {
+            _ if first != 0x00fd => release,
+            23026..=24247 => " (Visual Studio 2015)",
+            25008..=27051 => " (Visual Studio 2017)",
+            27508..=30159 => " (Visual Studio 2019)",
+            30401.. => " (Visual Studio 2022)",
+            _ => "",
+        };
+        format!("{name}{release}")
+    }
+}
+
+impl RichHeader {
+    pub fn new(mmap: &[u8], nt_offset: usize) -> Option<RichHeader> {
+        const DOS_HEADER_SIZE: usize = 0x40;
+        let end = nt_offset.min(mmap.len());
+
+        // the "Rich" marker is in clear text, followed by the key
+        let rich = (DOS_HEADER_SIZE..end.saturating_sub(7))
+            .step_by(4)
+            .find(|i| get_u32(mmap, *i) == RICH_SIGNATURE)?;
+        let key = get_u32(mmap, rich + 4);
+        let offset = (DOS_HEADER_SIZE..rich)
+            .step_by(4)
+            .rev()
+            .find(|i| get_u32(mmap, *i) ^ key == DANS_SIGNATURE)?;
+
+        // "DanS" is followed by three zero dwords, then (comp id, count) pairs
+        let entries = (offset + 16..rich)
+            .step_by(8)
+            .map(|entry| {
+                let comp_id = get_u32(mmap, entry) ^ key;
+                RichEntry {
+                    product_id: (comp_id >> 16) as u16,
+                    build: comp_id as u16,
+                    count: get_u32(mmap, entry + 4) ^ key,
+                }
+            })
+            .collect::<Vec<RichEntry>>();
+
+        // every byte before the header, skipping e_lfanew, rotated by its offset
+        let checksum = mmap[..offset]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !(0x3c..0x40).contains(i))
+            .fold(offset as u32, |sum, (i, b)| {
+                sum.wrapping_add((*b as u32).rotate_left(i as u32))
+            });
+        let checksum = entries.iter().fold(checksum, |sum, e| {
+            let comp_id = (e.product_id as u32) << 16 | e.build as u32;
+            sum.wrapping_add(comp_id.rotate_left(e.count))
+        });
+
+        Some(RichHeader {
+            offset,
+            key,
+            entries,
+            checksum,
+        })
+    }
+
+    /// The key is the checksum, so a mismatch means the header or DOS stub was edited.
+    pub fn is_valid(&self) -> bool {
+        self.checksum == self.key
+    }
+
+    pub fn show(&self) {
+        println!("\n================ rich header ================");
+        println!("offset:\t\t{:#x}", self.offset);
+        println!("key:\t\t{:#010x}", self.key);
+        let status = if self.is_valid() { "valid" } else { "INVALID" };
+        println!("checksum:\t{:#010x} ({status})", self.checksum);
+        println!("{:<10}{:<8}{:<8}tool", "prodid", "build", "count");
+        for entry in self.entries.iter() {
+            println!(
+                "{:#06x}    {:<8}{:<8}{}",
+                entry.product_id,
+                entry.build,
+                entry.count,
+                entry.tool_name()
+            );
+        }
+    }
+}
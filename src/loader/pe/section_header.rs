@@ -1,30 +1,70 @@
-use crate::loader::{get_u16, get_u32, get_u64};
-use iced_x86::{Decoder, DecoderOptions, Formatter, Instruction, NasmFormatter};
+use crate::disassembler::symbol::SymbolMap;
+use crate::disassembler::{self, FormatOptions, Isa};
+use crate::loader::pe::coff_symbol::{get_string, CoffSymbol};
+use crate::loader::{get_u16, get_u32};
 
 pub struct SectionHeader {
     pub name: String,
-    virtual_size: u32,
-    virtual_address: u32,
+    pub virtual_size: u32,
+    pub virtual_address: u32,
     pub size_of_raw_data: u32,
     pub pointer_to_raw_data: u32,
     pointer_to_relocations: u32,
     pointer_to_linenumbers: u32,
     number_of_relocations: u16,
     number_of_linenumbers: u16,
-    characteristics: u32,
+    pub characteristics: u32,
+}
+
+/// file offset of `rva`, if a section maps it
+pub fn rva_to_offset(sect_headers: &[SectionHeader], rva: u32) -> Option<usize> {
+    sect_headers
+        .iter()
+        .find(|s| {
+            let size = s.virtual_size.max(s.size_of_raw_data);
+            (s.virtual_address..s.virtual_address.saturating_add(size)).contains(&rva)
+        })
+        .map(|s| s.pointer_to_raw_data as usize + (rva - s.virtual_address) as usize)
 }
 
 impl SectionHeader {
-    pub fn new(mmap: &[u8], sect_num: usize, header_start: usize) -> Vec<SectionHeader> {
+    /// Names longer than 8 bytes are stored as `/N`, an offset into the COFF string table
+    /// (or `//` and base64 for offsets that do not fit in 7 digits).
+    fn get_name(raw: &[u8], mmap: &[u8], string_table: Option<usize>) -> String {
+        let name = CoffSymbol::short_name(raw);
+        let offset = match (name.strip_prefix("//"), name.strip_prefix('/')) {
+            (Some(base64), _) => base64.bytes().try_fold(0_u64, |acc, c| {
+                let digit = match c {
+                    b'A'..=b'Z' => c - b'A',
+                    b'a'..=b'z' => c - b'a' + 26,
+                    b'0'..=b'9' => c - b'0' + 52,
+                    b'+' => 62,
+                    b'/' => 63,
+                    _ => return None,
+                };
+                Some(acc << 6 | digit as u64)
+            }),
+            (None, Some(decimal)) => decimal.parse::<u64>().ok(),
+            (None, None) => None,
+        };
+
+        match (offset, string_table) {
+            (Some(offset), Some(string_table)) => get_string(mmap, string_table, offset as u32),
+            _ => name,
+        }
+    }
+
+    pub fn new(
+        mmap: &[u8],
+        sect_num: usize,
+        header_start: usize,
+        string_table: Option<usize>,
+    ) -> Vec<SectionHeader> {
         const SECT_SIZE: usize = 40;
         let mut section_headers = Vec::new();
-        for offset in (header_start..sect_num * SECT_SIZE).step_by(SECT_SIZE) {
+        for offset in (header_start..header_start + sect_num * SECT_SIZE).step_by(SECT_SIZE) {
             section_headers.push(SectionHeader {
-                name: get_u64(mmap, offset)
-                    .to_le_bytes()
-                    .iter()
-                    .map(|b| *b as char)
-                    .collect::<String>(),
+                name: Self::get_name(&mmap[offset..offset + 8], mmap, string_table),
                 virtual_size: get_u32(mmap, offset + 8),
                 virtual_address: get_u32(mmap, offset + 12),
                 size_of_raw_data: get_u32(mmap, offset + 16),
@@ -59,46 +99,25 @@ impl SectionHeader {
         println!("characteristics:\t{:#x}", self.characteristics);
     }
 
-    pub fn dump(&self, mmap: &[u8]) {
-        const HEXBYTES_COLUMN_BYTE_LENGTH: usize = 10;
-        const EXAMPLE_CODE_BITNESS: u32 = 64;
-        #[allow(non_snake_case)]
-        let EXAMPLE_CODE_RIP: u64 = self.virtual_address as u64;
+    pub fn dump(
+        &self,
+        mmap: &[u8],
+        isa: Isa,
+        image_base: u64,
+        options: &FormatOptions,
+        symbols: &SymbolMap,
+    ) {
+        let code_rip: u64 = image_base + self.virtual_address as u64;
         if self.characteristics & 0x00000020 != 0x0 {
             let bytes = &mmap[self.pointer_to_raw_data as usize
                 ..(self.pointer_to_raw_data + self.size_of_raw_data) as usize];
-            let mut decoder = Decoder::with_ip(
-                EXAMPLE_CODE_BITNESS,
-                bytes,
-                EXAMPLE_CODE_RIP,
-                DecoderOptions::NONE,
-            );
-            let mut formatter = NasmFormatter::new();
-
-            formatter.options_mut().set_digit_separator("`");
-            formatter.options_mut().set_first_operand_char_index(10);
-
-            let mut output = String::new();
-
-            let mut instruction = Instruction::default();
+            let mut decoder = isa.disassembler(bytes, code_rip, &[], options, symbols);
             while decoder.can_decode() {
-                decoder.decode_out(&mut instruction);
-
-                output.clear();
-                formatter.format(&instruction, &mut output);
-
-                print!("{:016X} ", instruction.ip());
-                let start_index = (instruction.ip() - EXAMPLE_CODE_RIP) as usize;
-                let instr_bytes = &bytes[start_index..start_index + instruction.len()];
-                for b in instr_bytes.iter() {
-                    print!("{b:02X}");
-                }
-                if instr_bytes.len() < HEXBYTES_COLUMN_BYTE_LENGTH {
-                    for _ in 0..HEXBYTES_COLUMN_BYTE_LENGTH - instr_bytes.len() {
-                        print!("  ");
-                    }
+                let instruction = decoder.decode();
+                if let Some(name) = symbols.label(instruction.ip) {
+                    disassembler::print_label(instruction.ip, name);
                 }
-                println!(" {output}");
+                disassembler::print_line(&instruction);
             }
         }
     }
@@ -0,0 +1,106 @@
+use crate::loader::pe::nt_headers::IMAGE_DIRECTORY_ENTRY_SECURITY;
+use crate::loader::pe::PeLoader;
+
+const IMAGE_FILE_RELOCS_STRIPPED: u16 = 0x0001;
+
+const IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA: u16 = 0x0020;
+const IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE: u16 = 0x0040;
+const IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY: u16 = 0x0080;
+const IMAGE_DLLCHARACTERISTICS_NX_COMPAT: u16 = 0x0100;
+const IMAGE_DLLCHARACTERISTICS_NO_SEH: u16 = 0x0400;
+const IMAGE_DLLCHARACTERISTICS_GUARD_CF: u16 = 0x4000;
+
+const IMAGE_GUARD_CF_INSTRUMENTED: u32 = 0x100;
+
+pub enum SafeSeh {
+    /// x64/ARM use table based exception handling
+    NotApplicable,
+    NoSeh,
+    Handlers(u64),
+    Missing,
+}
+
+pub struct SecurityReport {
+    pub aslr: bool,
+    pub relocs_stripped: bool,
+    pub high_entropy_va: bool,
+    pub dep: bool,
+    pub cfg: bool,
+    /// entries in the GuardCF function table
+    pub cfg_functions: u64,
+    pub safe_seh: SafeSeh,
+    pub gs_cookie: bool,
+    pub force_integrity: bool,
+    /// an Authenticode certificate table is present (the signature itself is not verified)
+    pub signed: bool,
+}
+
+impl SecurityReport {
+    pub fn new(loader: &PeLoader) -> SecurityReport {
+        let nt = &loader.nt_headers;
+        let dll_characteristics = nt.dll_characteristics();
+        let has = |flag: u16| dll_characteristics & flag != 0;
+        let load_config = loader.load_config.as_ref();
+
+        let safe_seh = if nt.bitness() == 64 {
+            SafeSeh::NotApplicable
+        } else if has(IMAGE_DLLCHARACTERISTICS_NO_SEH) {
+            SafeSeh::NoSeh
+        } else {
+            match load_config.filter(|c| c.se_handler_table != 0) {
+                Some(c) => SafeSeh::Handlers(c.se_handler_count),
+                None => SafeSeh::Missing,
+            }
+        };
+
+        SecurityReport {
+            aslr: has(IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE),
+            relocs_stripped: nt.characteristics() & IMAGE_FILE_RELOCS_STRIPPED != 0,
+            high_entropy_va: has(IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA),
+            dep: has(IMAGE_DLLCHARACTERISTICS_NX_COMPAT),
+            cfg: has(IMAGE_DLLCHARACTERISTICS_GUARD_CF)
+                && load_config.is_some_and(|c| {
+                    c.guard_flags & IMAGE_GUARD_CF_INSTRUMENTED != 0
+                        || c.guard_cf_function_table != 0
+                }),
+            cfg_functions: load_config.map_or(0, |c| c.guard_cf_function_count),
+            safe_seh,
+            gs_cookie: load_config.is_some_and(|c| c.security_cookie != 0),
+            force_integrity: has(IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY),
+            signed: nt.data_directory(IMAGE_DIRECTORY_ENTRY_SECURITY).is_some(),
+        }
+    }
+
+    pub fn show(&self) {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let aslr = match (self.aslr, self.relocs_stripped) {
+            (true, true) => "no (DYNAMIC_BASE set but relocations stripped)",
+            (true, false) => "yes",
+            (false, _) => "no",
+        };
+        let cfg = match self.cfg {
+            true => format!("yes ({} functions)", self.cfg_functions),
+            false => "no".to_string(),
+        };
+        let safe_seh = match self.safe_seh {
+            SafeSeh::NotApplicable => "n/a (table based exception handling)".to_string(),
+            SafeSeh::NoSeh => "n/a (NO_SEH)".to_string(),
+            SafeSeh::Handlers(count) => format!("yes ({count} handlers)"),
+            SafeSeh::Missing => "no".to_string(),
+        };
+        let signature = match self.signed {
+            true => "signed (certificate not verified)",
+            false => "unsigned",
+        };
+
+        println!("=== security ===");
+        println!("{:<18}{}", "ASLR:", aslr);
+        println!("{:<18}{}", "HIGH_ENTROPY_VA:", yes_no(self.high_entropy_va));
+        println!("{:<18}{}", "DEP:", yes_no(self.dep));
+        println!("{:<18}{}", "CFG:", cfg);
+        println!("{:<18}{}", "SafeSEH:", safe_seh);
+        println!("{:<18}{}", "/GS cookie:", yes_no(self.gs_cookie));
+        println!("{:<18}{}", "FORCE_INTEGRITY:", yes_no(self.force_integrity));
+        println!("{:<18}{}", "signature:", signature);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::loader::pe::section_header::{rva_to_offset, SectionHeader};
+use crate::loader::{get_u32, get_u64};
+
+/// IMAGE_TLS_DIRECTORY32/64. Addresses are VAs, not RVAs.
+pub struct TlsDirectory {
+    pub start_address_of_raw_data: u64,
+    pub end_address_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_callbacks: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+    /// VAs of the callbacks the loader runs before the entry point
+    pub callbacks: Vec<u64>,
+}
+
+impl TlsDirectory {
+    pub fn new(
+        mmap: &[u8],
+        offset: usize,
+        bitness: u32,
+        image_base: u64,
+        sect_headers: &[SectionHeader],
+    ) -> TlsDirectory {
+        let word = (bitness / 8) as usize;
+        let get_word = |offset: usize| match word {
+            8 => get_u64(mmap, offset),
+            _ => get_u32(mmap, offset) as u64,
+        };
+
+        // NULL terminated array of callback VAs
+        let address_of_callbacks = get_word(offset + 3 * word);
+        let mut callbacks = Vec::new();
+        if let Some(mut callback) = address_of_callbacks
+            .checked_sub(image_base)
+            .and_then(|rva| rva_to_offset(sect_headers, rva as u32))
+        {
+            while callback + word <= mmap.len() && get_word(callback) != 0 {
+                callbacks.push(get_word(callback));
+                callback += word;
+            }
+        }
+
+        TlsDirectory {
+            start_address_of_raw_data: get_word(offset),
+            end_address_of_raw_data: get_word(offset + word),
+            address_of_index: get_word(offset + 2 * word),
+            address_of_callbacks,
+            size_of_zero_fill: get_u32(mmap, offset + 4 * word),
+            characteristics: get_u32(mmap, offset + 4 * word + 4),
+            callbacks,
+        }
+    }
+
+    /// `name_of` labels a callback address, e.g. with its function name.
+    pub fn show(&self, name_of: impl Fn(u64) -> Option<String>) {
+        println!("=== TLS directory ===");
+        println!(
+            "{:<24}{:#x}",
+            "StartAddressOfRawData:", self.start_address_of_raw_data
+        );
+        println!(
+            "{:<24}{:#x}",
+            "EndAddressOfRawData:", self.end_address_of_raw_data
+        );
+        println!("{:<24}{:#x}", "AddressOfIndex:", self.address_of_index);
+        println!(
+            "{:<24}{:#x}",
+            "AddressOfCallBacks:", self.address_of_callbacks
+        );
+        println!("{:<24}{:#x}", "SizeOfZeroFill:", self.size_of_zero_fill);
+        println!("{:<24}{:#x}", "Characteristics:", self.characteristics);
+        println!("--- callbacks ---");
+        if self.callbacks.is_empty() {
+            println!("none");
+        }
+        for callback in self.callbacks.iter() {
+            println!(
+                "{:#018x}  {}",
+                callback,
+                name_of(*callback).unwrap_or_default()
+            );
+        }
+    }
+}
@@ -1,9 +1,48 @@
 #[cfg(test)]
 mod tests {
+    use crate::disassembler::FormatOptions;
+    use crate::loader::pe::nt_headers::{
+        IMAGE_DIRECTORY_ENTRY_EXPORT, IMAGE_DIRECTORY_ENTRY_IMPORT, IMAGE_DIRECTORY_ENTRY_TLS,
+    };
+    use crate::loader::pe::security::SafeSeh;
+    use crate::loader::Loader;
     use crate::{loader, visualize};
     use memmap::Mmap;
     use std::fs::File;
 
+    /// PE32+ has no base_of_data and widens the image base and stack/heap sizes to 64 bits.
+    /// Both images use the linker's default stack and heap sizes.
+    fn assert_optional_header(
+        loader: &loader::pe::PeLoader,
+        magic: u16,
+        base_of_data: Option<u32>,
+        image_base: u64,
+        subsystem_version: (u16, u16),
+    ) {
+        let optional = &loader.nt_headers.optional_header;
+        assert_eq!(optional.magic, magic);
+        assert_eq!(optional.base_of_data, base_of_data);
+        assert_eq!(optional.image_base, image_base);
+        assert_eq!(optional.size_of_stack_reserve, 0x200000);
+        assert_eq!(optional.size_of_stack_commit, 0x1000);
+        assert_eq!(optional.size_of_heap_reserve, 0x100000);
+        assert_eq!(optional.size_of_heap_commit, 0x1000);
+        assert_eq!(
+            (
+                optional.major_operating_system_version,
+                optional.minor_operating_system_version
+            ),
+            (4, 0)
+        );
+        assert_eq!(
+            (
+                optional.major_subsystem_version,
+                optional.minor_subsystem_version
+            ),
+            subsystem_version
+        );
+    }
+
     #[test]
     fn pe_32_test() -> std::io::Result<()> {
         let filename = "./test/Pe32.exe";
@@ -16,12 +55,76 @@ mod tests {
         visualize::dump(&mapped_data);
         visualize::diff(&mapped_data, &other);
 
-        let loader = loader::pe::PeLoader::new(mapped_data);
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let report = loader.security_report();
+        assert!(report.aslr);
+        assert!(report.dep);
+        assert!(!report.high_entropy_va);
+        assert!(matches!(report.safe_seh, SafeSeh::Missing));
+        assert!(!report.cfg);
+        assert!(!report.signed);
+
+        // names longer than 8 bytes come from the COFF string table
+        let sections = loader
+            .sect_headers
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            sections,
+            [
+                ".text",
+                ".data",
+                ".rdata",
+                ".eh_frame",
+                ".bss",
+                ".idata",
+                ".CRT",
+                ".tls",
+                ".reloc",
+                ".debug_aranges",
+                ".debug_info",
+                ".debug_abbrev",
+                ".debug_line",
+                ".debug_frame",
+                ".debug_str",
+                ".debug_loc",
+                ".debug_ranges",
+                ".debug_line_str",
+            ]
+        );
+        assert_eq!(loader.symbols.len(), 1221);
+        let pre_c_init = loader.functions.iter().find(|f| f.name == "_pre_c_init");
+        assert_eq!(pre_c_init.map(|f| f.addr), Some(0x401010));
+
+        assert_optional_header(&loader, 0x10b, Some(0x9000), 0x400000, (4, 0));
+
+        // mingw registers __dyn_tls_init and __dyn_tls_dtor as TLS callbacks
+        let callbacks = loader.tls.as_ref().map(|tls| tls.callbacks.clone());
+        assert_eq!(callbacks, Some(vec![0x401730, 0x4016e0]));
+        // callbacks with a COFF symbol keep its name
+        let callback_names = [0x401730, 0x4016e0].map(|addr| {
+            loader
+                .functions
+                .iter()
+                .find(|f| f.addr == addr)
+                .map(|f| f.name.as_str())
+        });
+        assert_eq!(
+            callback_names,
+            [Some("___dyn_tls_init@12"), Some("___dyn_tls_dtor@12")]
+        );
+
         loader.header_show();
         loader.show_segment();
         loader.show_section();
-        loader.disassemble();
-        loader.analysis();
+        loader.show_security();
+        loader.show_relocations();
+        loader.show_tls();
+        loader.show_imports();
+        loader.show_symbols();
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
 
         Ok(())
     }
@@ -38,12 +141,341 @@ mod tests {
         visualize::dump(&mapped_data);
         visualize::diff(&mapped_data, &other);
 
-        let loader = loader::pe::PeLoader::new(mapped_data);
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let report = loader.security_report();
+        assert!(report.aslr);
+        assert!(report.dep);
+        assert!(report.high_entropy_va);
+        assert!(matches!(report.safe_seh, SafeSeh::NotApplicable));
+        assert!(!report.cfg);
+        assert!(!report.signed);
+
+        // names longer than 8 bytes come from the COFF string table
+        let sections = loader
+            .sect_headers
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            sections,
+            [
+                ".text",
+                ".data",
+                ".rdata",
+                ".eh_frame",
+                ".pdata",
+                ".xdata",
+                ".bss",
+                ".idata",
+                ".CRT",
+                ".tls",
+                ".reloc",
+                ".debug_aranges",
+                ".debug_info",
+                ".debug_abbrev",
+                ".debug_line",
+                ".debug_frame",
+                ".debug_str",
+                ".debug_loc",
+                ".debug_ranges",
+                ".debug_line_str",
+            ]
+        );
+        assert_eq!(loader.symbols.len(), 1327);
+        let pre_c_init = loader.functions.iter().find(|f| f.name == "pre_c_init");
+        assert_eq!(pre_c_init.map(|f| f.addr), Some(0x140001010));
+
+        assert_optional_header(&loader, 0x20b, None, 0x140000000, (5, 2));
+
+        // mingw registers __dyn_tls_init and __dyn_tls_dtor as TLS callbacks
+        let callbacks = loader.tls.as_ref().map(|tls| tls.callbacks.clone());
+        assert_eq!(callbacks, Some(vec![0x1400017b0, 0x140001780]));
+        // callbacks with a COFF symbol keep its name
+        let callback_names = [0x1400017b0, 0x140001780].map(|addr| {
+            loader
+                .functions
+                .iter()
+                .find(|f| f.addr == addr)
+                .map(|f| f.name.as_str())
+        });
+        assert_eq!(
+            callback_names,
+            [Some("__dyn_tls_init"), Some("__dyn_tls_dtor")]
+        );
+
+        // (rva, size) as listed by `objdump -p`
+        let directory = |index: usize| {
+            loader
+                .nt_headers
+                .data_directory(index)
+                .map(|d| (d.virtual_address, d.size))
+        };
+        assert_eq!(directory(IMAGE_DIRECTORY_ENTRY_EXPORT), None);
+        assert_eq!(
+            directory(IMAGE_DIRECTORY_ENTRY_IMPORT),
+            Some((0xe000, 0x7a4))
+        );
+        assert_eq!(directory(IMAGE_DIRECTORY_ENTRY_TLS), Some((0x9080, 0x28)));
+
         loader.header_show();
         loader.show_segment();
         loader.show_section();
-        loader.disassemble();
-        loader.analysis();
+        loader.show_security();
+        loader.show_relocations();
+        loader.show_tls();
+        loader.show_imports();
+        loader.show_symbols();
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_tls_stripped_test() -> std::io::Result<()> {
+        let filename = "./test/Pe64.exe";
+        let mut data = std::fs::read(filename)?;
+        // drop PointerToSymbolTable and NumberOfSymbols, as `strip` would
+        let file_header = u32::from_le_bytes(data[0x3c..0x40].try_into().unwrap()) as usize + 4;
+        data[file_header + 8..file_header + 16].fill(0);
+        let path = std::env::temp_dir().join("unlibit_pe_tls_stripped_test.exe");
+        std::fs::write(&path, &data)?;
+        let file = File::open(&path)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        assert!(loader.symbols.is_empty());
+        let functions = loader
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.addr))
+            .collect::<Vec<(&str, u64)>>();
+        // callbacks run before the entry point and are named in that order
+        assert_eq!(
+            functions,
+            [
+                ("tls_callback_0", 0x1400017b0),
+                ("tls_callback_1", 0x140001780),
+                ("entry_point", 0x1400014d0),
+            ]
+        );
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_imports_test() -> std::io::Result<()> {
+        let filename = "./test/PeImports.exe";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let imports = loader
+            .imports
+            .iter()
+            .map(|dll| {
+                let labels = dll.functions.iter().map(|f| f.label(&dll.name));
+                (
+                    dll.name.as_str(),
+                    dll.delay,
+                    labels.collect::<Vec<String>>(),
+                )
+            })
+            .collect::<Vec<(&str, bool, Vec<String>)>>();
+        assert_eq!(
+            imports,
+            [
+                (
+                    "KERNEL32.dll",
+                    false,
+                    vec!["ExitProcess".to_string(), "GetTickCount".to_string()]
+                ),
+                (
+                    "USER32.dll",
+                    true,
+                    vec!["MessageBeep".to_string(), "USER32.dll#17".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(loader.imports[1].functions[1].ordinal, Some(17));
+        assert_eq!(
+            loader.iat_map().get(&0x140004048).map(String::as_str),
+            Some("GetTickCount")
+        );
+
+        // indirect calls through the IAT are labelled with the imported name
+        let entry_point = loader
+            .functions
+            .iter()
+            .find(|f| f.name == "entry_point")
+            .unwrap();
+        let bytes = &loader.mem_data
+            [entry_point.offset as usize..(entry_point.offset + entry_point.size) as usize];
+        let mut decoder = loader.nt_headers.isa().disassembler(
+            bytes,
+            entry_point.addr,
+            &[],
+            &FormatOptions::default(),
+            &loader.symbol_map(),
+        );
+        let mut calls = Vec::new();
+        while decoder.can_decode() {
+            let instruction = decoder.decode();
+            if instruction.is_call() {
+                calls.push(instruction.operands);
+            }
+        }
+        assert_eq!(
+            calls,
+            [
+                "qword [rel GetTickCount]",
+                "qword [rel MessageBeep]",
+                "qword [rel USER32.dll#17]",
+                "qword [rel ExitProcess]",
+            ]
+        );
+
+        loader.show_section();
+        loader.show_imports();
+        loader.show_symbols();
+        loader.disassemble(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_exports_test() -> std::io::Result<()> {
+        let filename = "./test/PeExports.dll";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::new(mapped_data);
+        loader.show_exports();
+        loader.disassemble(&FormatOptions::default());
+        loader.analysis(&FormatOptions::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_exports_crafted_test() -> std::io::Result<()> {
+        let mut data = std::fs::read("./test/PeExports.dll")?;
+        // export data directory size and NumberOfFunctions of the .edata section at 0x600
+        data[0x10c..0x110].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[0x614..0x618].copy_from_slice(&u32::MAX.to_le_bytes());
+        let path = std::env::temp_dir().join("unlibit_pe_exports_crafted_test.dll");
+        std::fs::write(&path, &data)?;
+        let file = File::open(&path)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let exports = loader.exports.as_ref().expect("export directory");
+        // the function table is cut off at the end of .edata
+        assert!(exports.exports.len() <= 0x200 / 4);
+        let names = exports
+            .exports
+            .iter()
+            .filter_map(|e| e.name.as_deref())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, ["add", "mul", "RtlZeroMemory"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_relocate_test() -> std::io::Result<()> {
+        let filename = "./test/Pe32.exe";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+        let original = mapped_data.to_vec();
+
+        let loader = loader::pe::PeLoader::new(mapped_data);
+        assert_eq!(loader.relocate(0x0040_0000), Some(original.clone()));
+
+        // the HIGHLOW fixup at rva 0x1018 (.text starts at file offset 0x600) holds ImageBase
+        let relocated = loader.relocate(0x1000_0000).unwrap();
+        assert_eq!(original[0x618..0x61c], 0x0040_0000_u32.to_le_bytes());
+        assert_eq!(relocated[0x618..0x61c], 0x1000_0000_u32.to_le_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_resources_test() -> std::io::Result<()> {
+        let filename = "./test/PeResources.exe";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        loader.show_resources();
+
+        let version_info = loader.version_info().expect("VERSIONINFO resource");
+        assert_eq!(version_info.file_version, "1.2.3.4");
+        assert_eq!(version_info.product_version, "1.2.0.0");
+        let value = |key: &str| {
+            version_info
+                .strings
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(value("FileVersion"), Some("1.2.3.4"));
+        assert_eq!(value("ProductName"), Some("unlibit"));
+        assert_eq!(value("CompanyName"), Some("Unlibit Project"));
+
+        let manifest = String::from_utf8_lossy(loader.manifest().expect("manifest resource"));
+        assert!(manifest.contains(r#"name="Unlibit.PeResources" version="1.2.3.4""#));
+
+        let output = std::env::temp_dir().join("unlibit_pe_resources_test.bin");
+        loader.save_resource("10/HELLO/1033", output.to_str().unwrap())?;
+        assert_eq!(std::fs::read(&output)?, b"raw blob\0");
+        std::fs::remove_file(output)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_debug_directory_test() -> std::io::Result<()> {
+        let filename = "./test/PeDebug.exe";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::new(mapped_data);
+        loader.show_debug_directory();
+        assert_eq!(
+            loader.build_id().as_deref(),
+            Some("123456789ABCDEF00123456789ABCDEF3")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pe_rich_header_test() -> std::io::Result<()> {
+        let filename = "./test/PeRich.exe";
+        let file = File::open(filename)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let rich = loader.rich_header.as_ref().expect("rich header");
+        assert!(rich.is_valid());
+        assert_eq!(rich.entries.len(), 7);
+        loader.header_show();
+        loader.show_section();
+
+        // the checksum covers the DOS stub, so editing a stub byte invalidates it
+        let mut data = std::fs::read(filename)?;
+        data[0x50] ^= 0xff;
+        let path = std::env::temp_dir().join("unlibit_pe_rich_header_test.exe");
+        std::fs::write(&path, &data)?;
+        let file = File::open(&path)?;
+        let mapped_data = unsafe { Mmap::map(&file)? };
+
+        let loader = loader::pe::PeLoader::load(mapped_data);
+        let rich = loader.rich_header.as_ref().expect("rich header");
+        assert!(!rich.is_valid());
+        assert_eq!(rich.entries.len(), 7);
 
         Ok(())
     }
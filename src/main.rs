@@ -1,7 +1,9 @@
+mod disassembler;
 mod loader;
 mod visualize;
 
 use clap::{arg, AppSettings, ArgGroup};
+use disassembler::{FormatOptions, HexStyle, Syntax};
 use memmap::Mmap;
 use std::fs::File;
 
@@ -9,6 +11,7 @@ use std::fs::File;
 pub enum ExeOption {
     OPT_DEFAULT,
     OPT_HEADER,
+    OPT_ALL_HEADERS,
     OPT_PROG,
     OPT_SECT,
     OPT_DISASEM,
@@ -16,21 +19,96 @@ pub enum ExeOption {
     OPT_DIFF,
     OPT_ANALYSIS,
     OPT_HISTOGRAM,
+    OPT_DYNAMIC,
+    OPT_DYNSYM,
+    OPT_RELOCS,
+    OPT_NOTES,
+    OPT_SECURITY,
+    OPT_IMPORTS,
+    OPT_EXPORTS,
+    OPT_SYMBOLS,
+    OPT_DEBUG_DIRECTORY,
+    OPT_TLS,
+    OPT_RESOURCES,
+    OPT_SAVE_RESOURCE,
+    OPT_REBASE,
+}
+
+/// The id debuggers match a binary to its separate debug file or PDB by.
+fn show_build_id(loader: &dyn loader::Loader) {
+    if let Some(build_id) = loader.build_id() {
+        println!("\nbuild-id: {build_id}");
+    }
 }
 
 fn main() -> std::io::Result<()> {
     let app = clap::app_from_crate!()
         .arg(arg!(<filename> "target file path").group("ELF"))
         .arg(arg!(-h --header ... "Show header"))
+        .arg(arg!(--"all-headers" ... "Show the file header, segments and sections"))
         .arg(arg!(-p --program ... "Show all segments"))
         .arg(arg!(-s --section ... "Show all sections"))
         .arg(arg!(-d --disasem ... "Disassemble ELF/PE"))
         .arg(arg!(-a --analyze ... "Analyze target binaly file"))
+        .arg(arg!(--dynamic ... "Show dynamic section"))
+        .arg(arg!(--dynsym ... "Show imported and exported dynamic symbols"))
+        .arg(arg!(-r --relocs ... "Show relocations"))
+        .arg(arg!(--imports ... "Show imported DLLs and functions"))
+        .arg(arg!(--exports ... "Show exported functions"))
+        .arg(arg!(--symbols ... "Show the COFF symbol table"))
+        .arg(arg!(--"debug-dir" ... "Show the debug directory (PDB path, GUID, age)"))
+        .arg(arg!(--tls ... "Show the TLS directory and callbacks"))
+        .arg(arg!(--resources ... "Show resources, version info and manifest"))
+        .arg(
+            arg!(--"save-resource" <RESOURCE> "Save a resource (type/name/lang) to a file")
+                .required(false)
+                .value_names(&["TYPE/NAME/LANG", "OUTPUT"]),
+        )
+        .arg(
+            arg!(--rebase <ADDR> "Write a copy with base relocations applied for a load address")
+                .required(false)
+                .value_names(&["ADDR", "OUTPUT"]),
+        )
+        .arg(arg!(--security ... "Show hardening features (RELRO, NX, PIE, canary, ...)"))
+        .arg(arg!(-n --notes ... "Show notes (build-id, ABI tag, GNU properties)"))
         .group(
             ArgGroup::new("run option")
-                .args(&["header", "program", "section", "disasem", "analyze"])
+                .args(&[
+                    "header",
+                    "all-headers",
+                    "program",
+                    "section",
+                    "disasem",
+                    "analyze",
+                    "dynamic",
+                    "dynsym",
+                    "relocs",
+                    "notes",
+                    "security",
+                    "imports",
+                    "exports",
+                    "symbols",
+                    "debug-dir",
+                    "tls",
+                    "resources",
+                    "save-resource",
+                    "rebase",
+                ])
                 .required(false),
         )
+        .arg(
+            arg!(--syntax <SYNTAX> "Assembly syntax of x86 listings")
+                .required(false)
+                .possible_values(["att", "gas", "intel", "masm", "nasm"])
+                .default_value("nasm"),
+        )
+        .arg(arg!(--uppercase "Print mnemonics in uppercase").required(false))
+        .arg(
+            arg!(--"hex-style" <STYLE> "Hex number style of x86 listings")
+                .required(false)
+                .possible_values(["prefix", "suffix"]),
+        )
+        .arg(arg!(--"digit-separator" "Separate digits of x86 numbers with `_`").required(false))
         .arg(arg!(--dump ... "Dump binary file").required(false))
         .arg(arg!(--diff <other> ... "Take a diff of the binary files").required(false))
         .arg(arg!(--histogram ... "Show byte histogram").required(false))
@@ -42,28 +120,48 @@ fn main() -> std::io::Result<()> {
         None => panic!("please specify target ELF file."),
     };
 
-    let flag_map = || {
-        (
-            app.is_present("header"),
-            app.is_present("program"),
-            app.is_present("section"),
-            app.is_present("disasem"),
-            app.is_present("dump"),
-            app.is_present("diff"),
-            app.is_present("analyze"),
-            app.is_present("histogram"),
-        )
-    };
-    let exe_option = match flag_map() {
-        (true, _, _, _, _, _, _, _) => ExeOption::OPT_HEADER,
-        (_, true, _, _, _, _, _, _) => ExeOption::OPT_PROG,
-        (_, _, true, _, _, _, _, _) => ExeOption::OPT_SECT,
-        (_, _, _, true, _, _, _, _) => ExeOption::OPT_DISASEM,
-        (_, _, _, _, true, _, _, _) => ExeOption::OPT_DUMP,
-        (_, _, _, _, _, true, _, _) => ExeOption::OPT_DIFF,
-        (_, _, _, _, _, _, true, _) => ExeOption::OPT_ANALYSIS,
-        (_, _, _, _, _, _, _, true) => ExeOption::OPT_HISTOGRAM,
-        _ => ExeOption::OPT_DEFAULT,
+    let exe_option = [
+        ("header", ExeOption::OPT_HEADER),
+        ("all-headers", ExeOption::OPT_ALL_HEADERS),
+        ("program", ExeOption::OPT_PROG),
+        ("section", ExeOption::OPT_SECT),
+        ("disasem", ExeOption::OPT_DISASEM),
+        ("dump", ExeOption::OPT_DUMP),
+        ("diff", ExeOption::OPT_DIFF),
+        ("analyze", ExeOption::OPT_ANALYSIS),
+        ("histogram", ExeOption::OPT_HISTOGRAM),
+        ("dynamic", ExeOption::OPT_DYNAMIC),
+        ("dynsym", ExeOption::OPT_DYNSYM),
+        ("relocs", ExeOption::OPT_RELOCS),
+        ("notes", ExeOption::OPT_NOTES),
+        ("security", ExeOption::OPT_SECURITY),
+        ("imports", ExeOption::OPT_IMPORTS),
+        ("exports", ExeOption::OPT_EXPORTS),
+        ("symbols", ExeOption::OPT_SYMBOLS),
+        ("debug-dir", ExeOption::OPT_DEBUG_DIRECTORY),
+        ("tls", ExeOption::OPT_TLS),
+        ("resources", ExeOption::OPT_RESOURCES),
+        ("save-resource", ExeOption::OPT_SAVE_RESOURCE),
+        ("rebase", ExeOption::OPT_REBASE),
+    ]
+    .into_iter()
+    .find(|(flag, _)| app.is_present(flag))
+    .map_or(ExeOption::OPT_DEFAULT, |(_, exe_option)| exe_option);
+
+    let format_options = FormatOptions {
+        syntax: match app.value_of("syntax") {
+            Some("att") | Some("gas") => Syntax::Att,
+            Some("intel") => Syntax::Intel,
+            Some("masm") => Syntax::Masm,
+            _ => Syntax::Nasm,
+        },
+        uppercase: app.is_present("uppercase"),
+        hex_style: match app.value_of("hex-style") {
+            Some("prefix") => Some(HexStyle::Prefix),
+            Some("suffix") => Some(HexStyle::Suffix),
+            _ => None,
+        },
+        digit_separator: app.is_present("digit-separator"),
     };
 
     let file = File::open(filename)?;
@@ -93,10 +191,50 @@ fn main() -> std::io::Result<()> {
             match exe_option {
                 ExeOption::OPT_DEFAULT => loader.header_show(),
                 ExeOption::OPT_HEADER => loader.header_show(),
+                ExeOption::OPT_ALL_HEADERS => loader.show_all_header(),
                 ExeOption::OPT_PROG => loader.show_segment(),
                 ExeOption::OPT_SECT => loader.show_section(),
-                ExeOption::OPT_DISASEM => loader.disassemble(),
-                ExeOption::OPT_ANALYSIS => loader.analysis(),
+                ExeOption::OPT_DISASEM => loader.disassemble(&format_options),
+                ExeOption::OPT_ANALYSIS => loader.analysis(&format_options),
+                ExeOption::OPT_DYNAMIC => loader.show_dynamic(),
+                ExeOption::OPT_DYNSYM => loader.show_dynamic_symbols(),
+                ExeOption::OPT_RELOCS => loader.show_relocations(),
+                ExeOption::OPT_NOTES => {
+                    loader.show_notes();
+                    show_build_id(loader.as_ref());
+                }
+                ExeOption::OPT_SECURITY => loader.show_security(),
+                ExeOption::OPT_IMPORTS => loader.show_imports(),
+                ExeOption::OPT_EXPORTS => loader.show_exports(),
+                ExeOption::OPT_SYMBOLS => loader.show_symbols(),
+                ExeOption::OPT_DEBUG_DIRECTORY => {
+                    loader.show_debug_directory();
+                    show_build_id(loader.as_ref());
+                }
+                ExeOption::OPT_TLS => loader.show_tls(),
+                ExeOption::OPT_RESOURCES => loader.show_resources(),
+                ExeOption::OPT_SAVE_RESOURCE => {
+                    let args = app
+                        .values_of("save-resource")
+                        .expect("please specify a resource and an output file")
+                        .collect::<Vec<&str>>();
+                    loader.save_resource(args[0], args[1])?;
+                }
+                ExeOption::OPT_REBASE => {
+                    let args = app
+                        .values_of("rebase")
+                        .expect("please specify a load address and an output file")
+                        .collect::<Vec<&str>>();
+                    let load_base = match args[0].strip_prefix("0x") {
+                        Some(hex) => u64::from_str_radix(hex, 16),
+                        None => args[0].parse(),
+                    }
+                    .expect("load address must be a decimal or 0x-prefixed hex number");
+                    match loader.relocate(load_base) {
+                        Some(data) => std::fs::write(args[1], data)?,
+                        None => println!("base relocations are not supported for this format"),
+                    }
+                }
                 _ => unreachable!(),
             }
         }